@@ -1,7 +1,41 @@
+use serde::Deserialize;
 use std::error::Error;
 use std::process::{Command, Stdio};
+use std::sync::Arc;
 
-pub trait Clipboard {
+// Which clipboard backend to use, selectable from the CLI or the config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardKind {
+    Wayland,
+    None,
+}
+
+impl ClipboardKind {
+    // Uses Wayland when a compositor is running and `wl-copy` is installed.
+    pub fn detect() -> Self {
+        let has_wl_copy = std::env::var_os("PATH").is_some_and(|paths| {
+            std::env::split_paths(&paths).any(|dir| dir.join("wl-copy").is_file())
+        });
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() && has_wl_copy {
+            ClipboardKind::Wayland
+        } else {
+            ClipboardKind::None
+        }
+    }
+
+    pub fn create(self) -> Arc<dyn Clipboard> {
+        match self {
+            ClipboardKind::Wayland => Arc::new(WaylandClipboard),
+            ClipboardKind::None => Arc::new(NoopClipboard),
+        }
+    }
+}
+
+// Implementations may block on external commands, so callers run them on a
+// worker thread.
+pub trait Clipboard: Send + Sync {
+    fn kind(&self) -> ClipboardKind;
     fn copy(&self, text: &str) -> Result<(), Box<dyn Error>>;
     fn paste(&self) -> Result<String, Box<dyn Error>>;
 }
@@ -9,6 +43,10 @@ pub trait Clipboard {
 pub struct WaylandClipboard;
 
 impl Clipboard for WaylandClipboard {
+    fn kind(&self) -> ClipboardKind {
+        ClipboardKind::Wayland
+    }
+
     fn copy(&self, text: &str) -> Result<(), Box<dyn Error>> {
         let mut child = Command::new("wl-copy")
             .arg(text)
@@ -32,11 +70,7 @@ impl Clipboard for WaylandClipboard {
                         .unwrap_or_else(|_| "(Failed to read stderr)".to_string())
                 },
             );
-            Err(format!(
-                "wl-copy failed with status: {:?}, stderr: {}",
-                status, stderr
-            )
-            .into())
+            Err(format!("wl-copy failed ({}): {}", status, stderr.trim()).into())
         }
     }
 
@@ -57,25 +91,27 @@ impl Clipboard for WaylandClipboard {
                 .map_err(|e| format!("Failed to decode wl-paste output: {}", e).into())
         } else {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!(
-                "wl-paste failed with status: {:?}, stderr: {}",
-                output.status, stderr
-            )
-            .into())
+            Err(format!("wl-paste failed ({}): {}", output.status, stderr.trim()).into())
         }
     }
 }
 
 // A no-op clipboard for environments where no system clipboard is available or supported.
-#[allow(dead_code)]
 pub struct NoopClipboard;
 
+pub const NO_CLIPBOARD_HINT: &str =
+    "no clipboard backend detected; install wl-clipboard or pass --clipboard wayland";
+
 impl Clipboard for NoopClipboard {
+    fn kind(&self) -> ClipboardKind {
+        ClipboardKind::None
+    }
+
     fn copy(&self, _text: &str) -> Result<(), Box<dyn Error>> {
-        Err("No system clipboard available or supported.".into())
+        Err(NO_CLIPBOARD_HINT.into())
     }
 
     fn paste(&self) -> Result<String, Box<dyn Error>> {
-        Err("No system clipboard available or supported.".into())
+        Err(NO_CLIPBOARD_HINT.into())
     }
 }
@@ -0,0 +1,58 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+
+use crate::clipboard::ClipboardKind;
+use crate::keymap::{Action, KeyList};
+use crate::theme::ThemeName;
+
+// Defaults read from `twoson.toml` in the current directory, falling back to
+// `$XDG_CONFIG_HOME/twoson/config.toml`. Command-line flags take precedence.
+// There is deliberately no `separator` entry: key paths are always split on
+// `.` and no flag changes that, so a `separator = ...` line is rejected as an
+// unknown field rather than silently ignored.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    pub color: Option<bool>,
+    pub scrolloff: Option<usize>,
+    pub clipboard: Option<ClipboardKind>,
+    pub theme: Option<ThemeName>,
+    pub fold_accents: Option<bool>,
+    pub plural_categories: Option<Vec<String>>,
+    pub mt_url: Option<String>,
+    // Regexes matching placeholders, replacing the built-in `{x}`/`{{x}}`/printf
+    // pattern, e.g. `[":[a-z_]+", "\\{\\{ *\\w+ *\\}\\}"]`
+    pub placeholder_patterns: Vec<String>,
+    // Keys whose translation may differ from others sharing their source text
+    pub allow_divergent: Vec<String>,
+    // Action name to key(s), e.g. `next = ["n", "down"]` or `open-finder = "ctrl+t"`
+    pub keys: HashMap<Action, KeyList>,
+}
+
+impl Config {
+    // A missing config file is not an error; an unreadable or invalid one is.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let Some(path) = Self::find_path() else {
+            return Ok(Config::default());
+        };
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        toml::from_str(&contents)
+            .map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into())
+    }
+
+    fn find_path() -> Option<PathBuf> {
+        let local = PathBuf::from("twoson.toml");
+        if local.is_file() {
+            return Some(local);
+        }
+        let config_home = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        let global = config_home.join("twoson").join("config.toml");
+        global.is_file().then_some(global)
+    }
+}
@@ -0,0 +1,68 @@
+use indexmap::IndexMap;
+
+// `dotted.key=value` lines, properties-style. Backslash escapes `\n`, `\r`,
+// `\t`, `\=` and `\\` let keys and values hold newlines and equals signs; blank
+// lines and lines starting with `#` are skipped.
+pub fn parse(text: &str) -> Result<IndexMap<String, String>, String> {
+    let mut entries = IndexMap::new();
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let Some(split) = separator(line) else {
+            return Err(format!("line {}: expected key=value", number + 1));
+        };
+        let key = unescape(line[..split].trim());
+        let value = unescape(&line[split + 1..]);
+        entries.insert(key, value);
+    }
+    Ok(entries)
+}
+
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '=' => escaped.push_str("\\="),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Byte index of the first `=` that isn't escaped.
+fn separator(line: &str) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '=' => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
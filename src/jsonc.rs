@@ -0,0 +1,116 @@
+use std::collections::HashMap;
+
+// Blanks out `//` and `/* */` comments so the rest parses as plain JSON, keeping
+// every newline so parse errors still point at the right line and column.
+// Also returns the comments written above each key, by dotted path; a comment
+// after something else on the same line is treated as trailing and dropped.
+pub fn strip_comments(text: &str) -> (String, HashMap<String, String>) {
+    let mut stripped = String::with_capacity(text.len());
+    let mut comments = HashMap::new();
+    let mut pending: Vec<String> = Vec::new();
+    // One entry per open object: whether it pushed a segment onto `path`
+    let mut objects: Vec<bool> = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut last_key: Option<String> = None;
+    let mut line_has_token = false;
+
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '/' if matches!(chars.peek(), Some((_, '/'))) => {
+                let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+                if !line_has_token {
+                    pending.push(text[start + 2..end].trim().to_string());
+                }
+                blank(&mut stripped, &text[start..end]);
+                while chars.peek().is_some_and(|(i, _)| *i < end) {
+                    chars.next();
+                }
+            }
+            '/' if matches!(chars.peek(), Some((_, '*'))) => {
+                let end = text[start + 2..]
+                    .find("*/")
+                    .map_or(text.len(), |i| start + 2 + i + 2);
+                if !line_has_token {
+                    let body = text[start + 2..end].trim_end_matches("*/");
+                    pending.extend(
+                        body.lines()
+                            .map(|line| line.trim().trim_start_matches('*').trim().to_string())
+                            .filter(|line| !line.is_empty()),
+                    );
+                }
+                blank(&mut stripped, &text[start..end]);
+                while chars.peek().is_some_and(|(i, _)| *i < end) {
+                    chars.next();
+                }
+            }
+            '"' => {
+                let mut end = start + 1;
+                let mut escaped = false;
+                for (i, c) in text[start + 1..].char_indices() {
+                    end = start + 1 + i + c.len_utf8();
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                stripped.push_str(&text[start..end]);
+                while chars.peek().is_some_and(|(i, _)| *i < end) {
+                    chars.next();
+                }
+                line_has_token = true;
+
+                if text[end..].trim_start().starts_with(':') {
+                    let key: String = serde_json::from_str(&text[start..end])
+                        .unwrap_or_else(|_| text[start + 1..end - 1].to_string());
+                    if !pending.is_empty() {
+                        let mut full_path = path.clone();
+                        full_path.push(key.clone());
+                        comments.insert(full_path.join("."), pending.join("\n"));
+                        pending.clear();
+                    }
+                    last_key = Some(key);
+                }
+            }
+            '{' => {
+                match last_key.take() {
+                    Some(key) => {
+                        path.push(key);
+                        objects.push(true);
+                    }
+                    None => objects.push(false),
+                }
+                stripped.push(c);
+                line_has_token = true;
+            }
+            '}' => {
+                if objects.pop() == Some(true) {
+                    path.pop();
+                }
+                pending.clear();
+                stripped.push(c);
+                line_has_token = true;
+            }
+            '\n' => {
+                stripped.push(c);
+                line_has_token = false;
+            }
+            _ => {
+                if !c.is_whitespace() && c != ',' && c != ':' {
+                    last_key = None;
+                }
+                if !c.is_whitespace() {
+                    line_has_token = true;
+                }
+                stripped.push(c);
+            }
+        }
+    }
+    (stripped, comments)
+}
+
+fn blank(stripped: &mut String, comment: &str) {
+    stripped.extend(comment.chars().map(|c| if c == '\n' { '\n' } else { ' ' }));
+}
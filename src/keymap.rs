@@ -0,0 +1,352 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+// Everything a key can trigger in normal mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    Quit,
+    SaveAndQuit,
+    Save,
+    SaveAs,
+    Reload,
+    Next,
+    Previous,
+    ToggleExpand,
+    ToggleSubtree,
+    Expand,
+    Collapse,
+    JumpToParent,
+    JumpToFirstChild,
+    ExpandToDepth,
+    Edit,
+    ExternalEdit,
+    Copy,
+    CopyKey,
+    CopySnippet,
+    CopyUntranslated,
+    OpenMachineTranslation,
+    Paste,
+    PasteLines,
+    MarkEmpty,
+    FillUntranslated,
+    FixWhitespace,
+    Undo,
+    ShowDiff,
+    Search,
+    ClearSearch,
+    Replace,
+    OpenFinder,
+    GotoKey,
+    ToggleBookmark,
+    NextBookmark,
+    EditNote,
+    FilterUntranslated,
+    FilterTranslated,
+    ClearFilters,
+    CycleHeader,
+    ToggleWrap,
+    VisualSelect,
+    UnifyDivergent,
+    ToggleScope,
+}
+
+impl Action {
+    // Actions that change translations, notes or the output file, all refused
+    // under `--readonly`
+    pub fn modifies(self) -> bool {
+        matches!(
+            self,
+            Action::Save
+                | Action::SaveAs
+                | Action::Edit
+                | Action::ExternalEdit
+                | Action::Paste
+                | Action::PasteLines
+                | Action::MarkEmpty
+                | Action::FillUntranslated
+                | Action::FixWhitespace
+                | Action::Undo
+                | Action::Replace
+                | Action::EditNote
+                | Action::UnifyDivergent
+        )
+    }
+
+    // The action's name in the config's `[keys]` table, e.g. `save-and-quit`.
+    pub fn name(self) -> String {
+        let mut name = String::new();
+        for c in format!("{:?}", self).chars() {
+            if c.is_ascii_uppercase() && !name.is_empty() {
+                name.push('-');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
+}
+
+// One key or a list of keys for an action in the config's `[keys]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+pub struct Keymap {
+    bindings: HashMap<(KeyCode, KeyModifiers), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let defaults: &[(Action, &[&str])] = &[
+            (Action::Quit, &["q"]),
+            (Action::SaveAndQuit, &["Z", "ctrl+x"]),
+            (Action::Save, &["s"]),
+            (Action::SaveAs, &["W"]),
+            (Action::Reload, &["R"]),
+            (Action::Next, &["j", "down"]),
+            (Action::Previous, &["k", "up"]),
+            (Action::ToggleExpand, &["space"]),
+            (Action::ToggleSubtree, &["O"]),
+            (Action::Expand, &["l", "right"]),
+            (Action::Collapse, &["h", "left"]),
+            (Action::JumpToParent, &["H"]),
+            (Action::JumpToFirstChild, &["L"]),
+            (Action::ExpandToDepth, &["z"]),
+            (Action::Edit, &["enter"]),
+            (Action::ExternalEdit, &["ctrl+e"]),
+            (Action::Copy, &["y"]),
+            (Action::CopyKey, &["Y"]),
+            (Action::CopySnippet, &["ctrl+y"]),
+            (Action::CopyUntranslated, &["ctrl+u"]),
+            (Action::OpenMachineTranslation, &["ctrl+o"]),
+            (Action::Paste, &["p"]),
+            (Action::PasteLines, &["P"]),
+            (Action::MarkEmpty, &["E"]),
+            (Action::FillUntranslated, &["ctrl+f"]),
+            (Action::FixWhitespace, &["ctrl+t"]),
+            (Action::Undo, &["u"]),
+            (Action::ShowDiff, &["D"]),
+            (Action::Search, &["/"]),
+            (Action::ClearSearch, &["esc"]),
+            (Action::Replace, &[":"]),
+            (Action::OpenFinder, &["ctrl+p"]),
+            (Action::GotoKey, &["G"]),
+            (Action::ToggleBookmark, &["m"]),
+            (Action::NextBookmark, &["'", "`"]),
+            (Action::EditNote, &["n"]),
+            (Action::FilterUntranslated, &["U"]),
+            (Action::FilterTranslated, &["T"]),
+            (Action::ClearFilters, &["A"]),
+            (Action::CycleHeader, &["N"]),
+            (Action::ToggleWrap, &["w"]),
+            (Action::VisualSelect, &["V"]),
+            (Action::UnifyDivergent, &["="]),
+            (Action::ToggleScope, &["S"]),
+        ];
+
+        let mut keymap = Keymap {
+            bindings: HashMap::new(),
+        };
+        for (action, keys) in defaults {
+            for key in *keys {
+                // The defaults are static, known to parse and not to overlap
+                keymap.bind(key, parse_key(key).unwrap(), *action).unwrap();
+            }
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    // Starts from the defaults; an action listed in `overrides` loses its default
+    // keys and gets exactly the configured ones. Digits without Ctrl are rejected,
+    // since they are read as a count prefix before the keymap is consulted, and so
+    // is a key that another action keeps by default or is also given.
+    pub fn with_overrides(overrides: &HashMap<Action, KeyList>) -> Result<Self, String> {
+        let mut keymap = Keymap::default();
+        keymap
+            .bindings
+            .retain(|_, action| !overrides.contains_key(action));
+        let mut overrides: Vec<_> = overrides.iter().collect();
+        overrides.sort_by_key(|(action, _)| action.name());
+        for (action, keys) in overrides {
+            for key in keys.keys() {
+                let (code, modifiers) = parse_key(key)?;
+                if let KeyCode::Char(c) = code
+                    && c.is_ascii_digit()
+                    && !modifiers.contains(KeyModifiers::CONTROL)
+                {
+                    return Err(format!(
+                        "Can't bind {:?}: digits are a count prefix (e.g. `2z`); use ctrl+{}",
+                        key, c
+                    ));
+                }
+                keymap.bind(key, (code, modifiers), *action)?;
+            }
+        }
+        Ok(keymap)
+    }
+
+    fn bind(
+        &mut self,
+        text: &str,
+        key: (KeyCode, KeyModifiers),
+        action: Action,
+    ) -> Result<(), String> {
+        match self.bindings.insert(key, action) {
+            Some(other) if other != action => Err(format!(
+                "{:?} is bound to both {} and {}; give one of them another key",
+                text,
+                other.name(),
+                action.name()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn action_for(&self, key: KeyEvent) -> Option<Action> {
+        self.bindings
+            .get(&normalize(key.code, key.modifiers))
+            .copied()
+    }
+}
+
+// Terminals report `R` as Char('R') with SHIFT set; the case of the character
+// already carries that information, so SHIFT is dropped for character keys.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(_) => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+// Parses keys such as `j`, `R`, `ctrl+p`, `alt+enter`, `shift+tab` or `f5`.
+pub fn parse_key(text: &str) -> Result<(KeyCode, KeyModifiers), String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = text.split('+').collect();
+    // A lone "+" (or a trailing "ctrl++") names the plus key itself
+    if text.ends_with("++") || text == "+" {
+        parts.retain(|p| !p.is_empty());
+        parts.push("+");
+    }
+    let (key, modifier_names) = parts
+        .split_last()
+        .ok_or_else(|| format!("Empty key binding: {:?}", text))?;
+    for name in modifier_names {
+        modifiers |= match name.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return Err(format!("Unknown modifier {:?} in {:?}", name, text)),
+        };
+    }
+
+    let code = match key.to_lowercase().as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "space" => KeyCode::Char(' '),
+        "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        lower if lower.len() > 1 && lower.starts_with('f') && lower[1..].parse::<u8>().is_ok() => {
+            KeyCode::F(lower[1..].parse().unwrap())
+        }
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return Err(format!("Unknown key {:?} in {:?}", key, text)),
+            }
+        }
+    };
+    Ok(normalize(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overriding(key: &str) -> Result<Keymap, String> {
+        Keymap::with_overrides(&HashMap::from([(
+            Action::Save,
+            KeyList::One(key.to_string()),
+        )]))
+    }
+
+    #[test]
+    fn default_keys_are_each_bound_once() {
+        let keymap = Keymap::default();
+        let key = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action_for(key), Some(Action::SaveAndQuit));
+        let key = KeyEvent::new(KeyCode::Char('Z'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action_for(key), Some(Action::SaveAndQuit));
+    }
+
+    #[test]
+    fn overrides_cannot_take_a_key_in_use() {
+        // `s` still saves by default
+        let error = Keymap::with_overrides(&HashMap::from([(
+            Action::Quit,
+            KeyList::One("s".to_string()),
+        )]))
+        .err()
+        .unwrap();
+        assert_eq!(
+            error,
+            r#""s" is bound to both save and quit; give one of them another key"#
+        );
+
+        let error = Keymap::with_overrides(&HashMap::from([
+            (Action::Quit, KeyList::One("ctrl+q".to_string())),
+            (
+                Action::SaveAndQuit,
+                KeyList::Many(vec!["Z".to_string(), "ctrl+q".to_string()]),
+            ),
+        ]))
+        .err()
+        .unwrap();
+        assert!(error.contains("quit and save-and-quit"), "{}", error);
+
+        // Moving `save` off `s` frees it for `quit`
+        let keymap = Keymap::with_overrides(&HashMap::from([
+            (Action::Quit, KeyList::One("s".to_string())),
+            (Action::Save, KeyList::One("ctrl+s".to_string())),
+        ]))
+        .unwrap();
+        let key = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(keymap.action_for(key), Some(Action::Quit));
+    }
+
+    #[test]
+    fn digits_without_ctrl_cannot_be_bound() {
+        assert!(overriding("2").is_err());
+        assert!(overriding("alt+2").is_err());
+
+        let keymap = overriding("ctrl+2").unwrap();
+        let key = KeyEvent::new(KeyCode::Char('2'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action_for(key), Some(Action::Save));
+    }
+}
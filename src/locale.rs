@@ -0,0 +1,53 @@
+use std::path::Path;
+
+// Guesses the target locale from names like `id_en.json`, `fr.json`,
+// `pt-BR.json` or `messages.de.json`.
+pub fn infer_locale(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    let candidates = [
+        Some(stem),
+        stem.split_once('_').map(|(prefix, _)| prefix),
+        stem.rsplit_once('.').map(|(_, suffix)| suffix),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|candidate| is_locale_code(candidate))
+        .map(|code| code.replace('_', "-"))
+}
+
+// A language subtag of two or three lowercase letters, optionally followed by
+// a region (`BR`, `419`) or script (`Hant`).
+pub fn is_locale_code(text: &str) -> bool {
+    let (language, rest) = match text.split_once(['-', '_']) {
+        Some((language, rest)) => (language, Some(rest)),
+        None => (text, None),
+    };
+    let language_ok =
+        (2..=3).contains(&language.len()) && language.chars().all(|c| c.is_ascii_lowercase());
+    let rest_ok = rest.is_none_or(|subtag| {
+        (subtag.len() == 2 && subtag.chars().all(|c| c.is_ascii_uppercase()))
+            || (subtag.len() == 3 && subtag.chars().all(|c| c.is_ascii_digit()))
+            || (subtag.len() == 4
+                && subtag.starts_with(|c: char| c.is_ascii_uppercase())
+                && subtag[1..].chars().all(|c| c.is_ascii_lowercase()))
+    });
+    language_ok && rest_ok
+}
+
+// Cardinal plural categories CLDR defines for common languages.
+pub fn plural_categories(locale: &str) -> Option<&'static [&'static str]> {
+    let language = locale.split(['-', '_']).next()?;
+    let categories: &[&str] = match language {
+        "id" | "ms" | "ja" | "ko" | "zh" | "th" | "vi" => &["other"],
+        "en" | "de" | "nl" | "sv" | "da" | "nb" | "fi" | "el" | "hu" | "bg" | "hi" | "tr" => {
+            &["one", "other"]
+        }
+        "fr" | "es" | "it" | "pt" | "ca" => &["one", "many", "other"],
+        "ro" => &["one", "few", "other"],
+        "ru" | "uk" | "pl" | "cs" | "sk" | "lt" => &["one", "few", "many", "other"],
+        "ar" => &["zero", "one", "two", "few", "many", "other"],
+        _ => return None,
+    };
+    Some(categories)
+}
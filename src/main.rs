@@ -1,38 +1,234 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
 use ratatui::{
     Frame, Terminal,
     backend::{Backend, CrosstermBackend},
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
+use regex::Regex;
 use std::{
+    collections::{HashMap, HashSet},
     error::Error,
-    io::{self},
+    ffi::OsStr,
+    io::{self, Write},
     path::PathBuf,
-    time::Instant,
+    sync::{
+        Arc,
+        mpsc::{Receiver, TryRecvError, channel},
+    },
+    time::{Duration, Instant},
 };
 use tui_textarea::TextArea;
+use unicode_width::UnicodeWidthStr;
 
 mod clipboard;
+mod config;
+mod flat;
+mod jsonc;
+mod keymap;
+mod locale;
+mod notes;
+mod opener;
+mod plural;
+mod report;
+mod session;
+mod source_hashes;
+mod theme;
 mod translation_data;
+mod watcher;
 
 use clap::Parser;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 pub struct Cli {
-    #[clap(short, long, value_parser)]
-    pub source_file: PathBuf,
+    #[clap(short, long, value_parser, required_unless_present = "edit")]
+    pub source_file: Option<PathBuf>,
     #[clap(short, long, value_parser)]
     pub out: Option<PathBuf>,
-    #[clap(long, action = clap::ArgAction::SetTrue, default_value_t = true)]
-    pub color: bool,
+    /// Prefix for the output file name when `--out` is omitted, e.g. `fr_`
+    #[clap(long, value_parser = parse_out_prefix, default_value = "id_")]
+    pub out_prefix: String,
+    /// Output file format; inferred from the output's extension by default
+    #[clap(long, value_enum)]
+    pub format: Option<FileFormat>,
+    /// Translation website to open with the selected source text, e.g.
+    /// `https://translate.google.com/?sl=auto&tl={target_lang}&text={text}`
+    #[clap(long, value_name = "TEMPLATE")]
+    pub mt_url: Option<String>,
+    /// Another locale's translations to show next to the source; never saved
+    #[clap(long, value_parser)]
+    pub reference: Option<PathBuf>,
+    /// Also write the keys translated differently than at startup to this file on every save
+    #[clap(long, value_name = "PATH")]
+    pub patch: Option<PathBuf>,
+    /// Edit the translations in this file in place; it serves as both source and output
+    #[clap(long, value_parser, conflicts_with_all = ["source_file", "out"])]
+    pub edit: Option<PathBuf>,
+    /// Use colors (`--color false` to disable); defaults to on unless NO_COLOR is set
+    #[clap(long, num_args = 0..=1, default_missing_value = "true")]
+    pub color: Option<bool>,
+    /// Clipboard backend for copy/paste
+    #[clap(long, value_enum)]
+    pub clipboard: Option<ClipboardKind>,
+    /// Color theme; `mono` disables colors
+    #[clap(long, value_enum)]
+    pub theme: Option<ThemeName>,
+    /// Watch the source file and offer to reload it when it changes on disk
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub watch: bool,
+    /// Keep at least this many rows above and below the cursor (vim-style);
+    /// omit to keep the cursor centered
+    #[clap(long, value_parser)]
+    pub scrolloff: Option<usize>,
+    /// List keys whose translation is identical to the source text and exit
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with = "edit")]
+    pub flag_identical: bool,
+    /// List keys sharing a source text but translated differently, and exit
+    #[clap(long, action = clap::ArgAction::SetTrue, conflicts_with = "edit")]
+    pub flag_divergent: bool,
+    /// Keys allowed to be translated differently from others with the same source
+    #[clap(long, value_delimiter = ',')]
+    pub allow_divergent: Vec<String>,
+    /// Ignore whitespace-only differences when comparing translations to the source
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub normalize_whitespace: bool,
+    /// Shorthand for `--sort-output natural`
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub natural_sort: bool,
+    /// Key order in the tree and in saved files: as in the source, alphabetical,
+    /// or with numbers compared by value (item2 before item10)
+    #[clap(long, value_enum)]
+    pub sort_output: Option<SortOrder>,
+    /// Only show and count keys under this dotted prefix, e.g. `auth`
+    #[clap(long)]
+    pub namespace: Option<String>,
+    /// Draw connector lines between folders and their children in the key list
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub tree_lines: bool,
+    /// Match target keys to source keys regardless of case when there's no exact
+    /// match; they are saved with the source's casing. Keys differing only by
+    /// case can collide, and then only one of their translations is kept
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub ignore_key_case: bool,
+    /// Browse without being able to edit or save anything
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub readonly: bool,
+    /// Show a bar beside the key list shading how translated each stretch of it is
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub minimap: bool,
+    /// Draw the key list's status markers and connectors with ASCII only, for
+    /// fonts that lack glyphs like ✓
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub ascii: bool,
+    /// Order of folders and leaves among siblings in the tree
+    #[clap(long, value_enum, default_value_t = TreeOrder::Alpha)]
+    pub tree_order: TreeOrder,
+    /// Ignore accents when searching, so "uber" matches "Über"
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub fold_accents: bool,
+    /// Write a Markdown progress report to this path and exit
+    #[clap(long, value_parser)]
+    pub report_md: Option<PathBuf>,
+    /// List source keys missing from the target and target keys missing from the source, then exit
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub merge_report: bool,
+    /// Save unsaved changes automatically every N seconds (0 disables)
+    #[clap(long, value_parser, default_value_t = 0)]
+    pub autosave: u64,
+    /// Flatten JSON while parsing it, using less memory on very large files
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub stream: bool,
+    /// Never write the output file; print what the last save would have written on exit
+    /// (implied by `--out -`)
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub dry_run: bool,
+    /// Refuse to save while a translation is missing placeholders or plural categories
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub strict: bool,
+    /// Use the default output path even if an unrelated file already exists there
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub force: bool,
+    /// With a report or --flag-* mode, exit with status 2 when fewer than this
+    /// percentage of keys are translated, 1 when a translation drops a placeholder
+    /// or plural category (or on errors), and 0 otherwise
+    #[clap(long, value_name = "PERCENT")]
+    pub fail_under: Option<f64>,
+    /// Flag translations longer than this multiple of the source (0 disables)
+    #[clap(long, value_parser, default_value_t = 2.0)]
+    pub length_ratio: f64,
+    /// Target language (e.g. `id`, `pt-BR`); inferred from the output filename when omitted
+    #[clap(long)]
+    pub target_lang: Option<String>,
+    /// Plural categories every ICU plural block in a translation must keep;
+    /// defaults to the target language's CLDR categories
+    #[clap(long, value_delimiter = ',')]
+    pub plural_categories: Option<Vec<String>>,
+    /// Indentation of saved JSON: a number of spaces, or `tab`. By default the
+    /// existing output's (or else the source's) indentation, or 2 spaces
+    #[clap(long, value_parser = parse_indent)]
+    pub indent: Option<String>,
+    /// End saved files with a newline (`--final-newline false` to disable)
+    #[clap(long, num_args = 0..=1, default_missing_value = "true", default_value_t = true, action = clap::ArgAction::Set)]
+    pub final_newline: bool,
+    /// Create missing directories of the output path when saving
+    #[clap(long, action = clap::ArgAction::SetTrue)]
+    pub mkdir: bool,
+    /// What saved files hold for untranslated keys: the source text, an empty
+    /// string, or nothing. Filled keys load back as untranslated
+    #[clap(long, value_enum, default_value_t = FillMissing::Skip)]
+    pub fill_missing: FillMissing,
+    // Key binding overrides; only settable from the config file
+    #[clap(skip)]
+    pub keys: HashMap<Action, KeyList>,
+}
+
+impl Cli {
+    fn sort_order(&self) -> SortOrder {
+        match self.sort_output {
+            Some(sort) => sort,
+            None if self.natural_sort => SortOrder::Natural,
+            None => SortOrder::Alpha,
+        }
+    }
+
+    // Modes that print a report and exit without starting the TUI
+    fn is_report_mode(&self) -> bool {
+        self.flag_identical || self.flag_divergent || self.merge_report || self.report_md.is_some()
+    }
+
+    // The file keys and source texts come from; under `--edit` that's the edited file.
+    fn source_path(&self) -> &PathBuf {
+        self.edit
+            .as_ref()
+            .or(self.source_file.as_ref())
+            .expect("clap requires --source-file unless --edit is given")
+    }
+
+    // Fills every option not given on the command line from the config file.
+    fn merge_config(mut self, config: Config) -> Self {
+        self.color = self.color.or(config.color);
+        self.scrolloff = self.scrolloff.or(config.scrolloff);
+        self.clipboard = self.clipboard.or(config.clipboard);
+        self.theme = self.theme.or(config.theme);
+        self.fold_accents |= config.fold_accents.unwrap_or(false);
+        self.plural_categories = self.plural_categories.or(config.plural_categories);
+        self.mt_url = self.mt_url.or(config.mt_url);
+        self.allow_divergent.extend(config.allow_divergent);
+        self.keys = config.keys;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -43,91 +239,682 @@ pub struct TreeNode {
     pub children: Vec<TreeNode>,
     pub expanded: bool,
     pub fully_translated: bool,
+    // (translated, total) leaves at or below this node, as of the last status pass
+    pub progress: (usize, usize),
+    // How many children are listed when expanded; the rest sit behind a "… N more" row
+    pub revealed: usize,
 }
 
 impl TreeNode {
     pub fn is_leaf(&self) -> bool {
         self.children.is_empty()
     }
+
+    // (translated, total) over every leaf at or below this node
+    pub fn leaf_counts(&self) -> (usize, usize) {
+        if self.is_leaf() {
+            let translated = self.translation.as_ref().is_some_and(|t| t.is_translated());
+            return (translated as usize, 1);
+        }
+        self.children.iter().fold((0, 0), |(done, all), child| {
+            let (child_done, child_all) = child.leaf_counts();
+            (done + child_done, all + child_all)
+        })
+    }
+
+    pub fn is_translated(&self) -> bool {
+        self.translation.as_ref().is_some_and(|t| t.is_translated())
+    }
+
+    // Every leaf at or below this node, in tree order
+    pub fn leaves(&self) -> Vec<&TreeNode> {
+        let mut leaves = Vec::new();
+        self.collect_leaves(&mut leaves);
+        leaves
+    }
+
+    fn collect_leaves<'a>(&'a self, leaves: &mut Vec<&'a TreeNode>) {
+        if self.is_leaf() {
+            leaves.push(self);
+        }
+        for child in &self.children {
+            child.collect_leaves(leaves);
+        }
+    }
+
+    // Up to `limit` untranslated leaf keys below this node, in tree order
+    pub fn untranslated_leaves(&self, limit: usize) -> Vec<&str> {
+        self.leaves()
+            .into_iter()
+            .filter(|leaf| !leaf.is_translated())
+            .take(limit)
+            .map(|leaf| leaf.full_path.as_str())
+            .collect()
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum AppMode {
     Normal,
     Editing,
+    Diff,
+    Prompt,
+    Finder,
+    Summary,
+    Renames,
+}
+
+// How siblings are ordered in the tree; keys are always sorted within each group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TreeOrder {
+    Alpha,
+    FoldersFirst,
+    LeavesFirst,
+}
+
+// Restricts the key list to leaves in a given translation state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    All,
+    Untranslated,
+    Translated,
+}
+
+impl StatusFilter {
+    fn accepts(&self, node: &TreeNode) -> bool {
+        let translated = node.translation.as_ref().is_some_and(|t| t.is_translated());
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Untranslated => !translated,
+            StatusFilter::Translated => translated,
+        }
+    }
+}
+
+// What a submitted single-line prompt should do with its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    SaveAs,
+    Search,
+    Replace,
+    Note,
+    Goto,
+}
+
+impl PromptKind {
+    fn title(&self) -> &'static str {
+        match self {
+            PromptKind::SaveAs => "Save as (Enter to save, Esc to cancel)",
+            PromptKind::Search => "Search keys (Enter to filter, empty to clear)",
+            PromptKind::Replace => "Replace in translations: s/old/new/ (add r for regex)",
+            PromptKind::Note => "Note for translators (Enter to save, empty to remove)",
+            PromptKind::Goto => "Go to key (Tab to complete, Enter to jump)",
+        }
+    }
+}
+
+// Actions that touch many keys and wait for a y/n answer before running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingAction {
+    FillUntranslated,
+    FixWhitespace,
+    QuitUnsaved,
+    // The changes and their preview are kept in `App::pending_batch`
+    Replace,
+    // Clipboard lines paired with untranslated keys, kept the same way
+    PasteLines,
+}
+
+impl PendingAction {
+    fn prompt(&self) -> &'static str {
+        match self {
+            PendingAction::FillUntranslated => {
+                "Fill all untranslated keys with their source text? (y/n)"
+            }
+            PendingAction::FixWhitespace => {
+                "Make leading/trailing whitespace match the source in all flagged keys? (y/n)"
+            }
+            PendingAction::QuitUnsaved => {
+                "Unsaved changes! Save before quitting? (y = save, n = discard, Esc = cancel)"
+            }
+            PendingAction::Replace => "Apply the replacement? (y/n)",
+            PendingAction::PasteLines => "Paste the lines into the untranslated keys? (y/n)",
+        }
+    }
+}
+
+// Clipboard commands shell out and may block, so they run on a worker thread.
+enum ClipboardJob {
+    Copy { text: String, done_message: String },
+    Paste { path: String },
+    // One line per untranslated key under `path`, as copied by ctrl+u
+    PasteLines { path: String },
+}
+
+enum ClipboardOutcome {
+    Copied {
+        result: Result<(), String>,
+        done_message: String,
+    },
+    Pasted {
+        result: Result<String, String>,
+        path: String,
+    },
+    PastedLines {
+        result: Result<String, String>,
+        path: String,
+    },
+}
+
+// A `s/old/new/` substitution over target texts. Without flags `old` and `new`
+// are literal; with `r`, `old` is a regex and `new` may refer to groups as `$1`.
+struct Substitution {
+    pattern: Regex,
+    replacement: String,
+    is_regex: bool,
+}
+
+impl Substitution {
+    fn parse(input: &str) -> Result<Self, String> {
+        let body = input
+            .trim()
+            .trim_start_matches(':')
+            .strip_prefix("s/")
+            .ok_or("Expected s/old/new/")?;
+
+        // Split on `/`, keeping `\/` as a literal slash
+        let mut parts = vec![String::new()];
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek() == Some(&'/') => {
+                    parts.last_mut().unwrap().push('/');
+                    chars.next();
+                }
+                '/' => parts.push(String::new()),
+                _ => parts.last_mut().unwrap().push(c),
+            }
+        }
+        let (old, new, flags) = match parts.as_slice() {
+            [old, new] => (old, new, ""),
+            [old, new, flags] => (old, new, flags.as_str()),
+            _ => return Err("Expected s/old/new/".to_string()),
+        };
+        if old.is_empty() {
+            return Err("Nothing to replace.".to_string());
+        }
+        let is_regex = match flags {
+            "" => false,
+            "r" => true,
+            _ => return Err(format!("Unknown flags {:?}", flags)),
+        };
+        let pattern = if is_regex {
+            Regex::new(old).map_err(|e| format!("Invalid regex: {}", e))?
+        } else {
+            Regex::new(&regex::escape(old)).map_err(|e| e.to_string())?
+        };
+        Ok(Substitution {
+            pattern,
+            replacement: new.clone(),
+            is_regex,
+        })
+    }
+
+    // Returns the new text, or None when nothing matched.
+    fn apply(&self, text: &str) -> Option<String> {
+        if !self.pattern.is_match(text) {
+            return None;
+        }
+        let replaced = if self.is_regex {
+            self.pattern.replace_all(text, self.replacement.as_str())
+        } else {
+            self.pattern
+                .replace_all(text, regex::NoExpand(&self.replacement))
+        };
+        Some(replaced.into_owned())
+    }
 }
 
-use crate::clipboard::{Clipboard, WaylandClipboard};
-use crate::translation_data::{TranslationItem, TranslationStore};
+// (full_path, target_text, needs_review) for every key touched by one operation
+type ChangeBatch = Vec<(String, Option<String>, bool)>;
+
+use crate::clipboard::{Clipboard, ClipboardKind, NO_CLIPBOARD_HINT};
+use crate::config::Config;
+use crate::keymap::{Action, KeyList, Keymap};
+use crate::notes::Notes;
+use crate::session::SessionState;
+use crate::source_hashes::SourceHashes;
+use crate::theme::{Theme, ThemeName};
+use crate::translation_data::{
+    FileFormat, FillMissing, InvalidTargetError, KeyOrder, MergeReport, SortOrder, TranslationItem,
+    TranslationStore, detect_indent, divergent_groups, extract_placeholders, fold_for_search,
+    is_remote, is_stdio, parse_indent, parse_out_prefix,
+};
+use crate::watcher::SourceWatcher;
 
 pub struct App<'a> {
     tree: Vec<TreeNode>,
     visible_nodes: Vec<(String, usize)>, // (full_path, depth)
     selected_index: usize,
     textarea: TextArea<'a>,
+    // Position in the selected key's history while editing; `None` is the current text
+    history_cursor: Option<usize>,
     translation_store: TranslationStore,
     mode: AppMode,
+    source_path: PathBuf,
+    stream: bool,
+    // Loaded with `--edit`, where each text is its own source
+    in_place: bool,
+    dry_run: bool,
+    strict: bool,
+    // Leaf to open in the external editor once the key handler returns
+    external_edit: Option<String>,
+    session: SessionState,
+    notes: Notes,
+    // Source text hashes the translations were saved against, to spot stale ones;
+    // always empty under `--edit`, whose sources are the saved texts themselves
+    source_hashes: SourceHashes,
+    // Read-only texts from `--reference`, and the label they're shown under
+    reference: HashMap<String, String>,
+    reference_label: String,
+    mt_url: Option<String>,
+    patch_path: Option<PathBuf>,
+    // What the last save would have written under `--dry-run`
+    dry_run_output: Option<Vec<u8>>,
     output_path: PathBuf,
     status_message: Option<(String, Instant)>,
-    clipboard: Box<dyn Clipboard>,
+    clipboard: Arc<dyn Clipboard>,
+    // The running clipboard command and when it started, for the spinner
+    clipboard_job: Option<(Receiver<ClipboardOutcome>, Instant)>,
     color: bool,
+    source_watcher: Option<SourceWatcher>,
+    source_changed: bool,
+    scrolloff: Option<usize>,
+    normalize_whitespace: bool,
+    pending_count: Option<usize>,
+    search_query: Option<String>,
+    fold_accents: bool,
+    plural_categories: Vec<String>,
+    length_ratio: f64,
+    tree_order: TreeOrder,
+    // Header shows the selected top-level namespace's progress instead of the total
+    namespace_progress: bool,
+    tree_lines: bool,
+    ascii: bool,
+    minimap: bool,
+    readonly: bool,
+    // Cells of the mini-map, one per row, for mouse clicks
+    minimap_area: Rect,
+    // Dotted prefix the tree, navigation and progress are restricted to
+    scope: Option<String>,
+    // Keys sharing a source text with another key translated differently
+    divergent_keys: HashSet<String>,
+    allow_divergent: Vec<String>,
+    target_lang: Option<String>,
+    status_filter: StatusFilter,
+    autosave_interval: Option<Duration>,
+    last_save: Instant,
+    keymap: Keymap,
+    theme: Theme,
+    // Words in the source text of every untranslated key, cached for the header
+    remaining_words: usize,
+    prompt_kind: Option<PromptKind>,
+    prompt_input: TextArea<'a>,
+    finder_input: TextArea<'a>,
+    finder_results: Vec<String>,
+    finder_selected: usize,
+    list_offset: usize,
+    key_list_area: Rect,
+    last_click: Option<(usize, Instant)>,
+    pending_action: Option<PendingAction>,
+    undo_stack: Vec<ChangeBatch>,
+    // Changes from a `s/old/new/` prompt or a line paste, and their confirmation text
+    pending_batch: Option<(ChangeBatch, String)>,
+    // (orphaned key, new key, translation) pairs offered on startup
+    rename_suggestions: Vec<(String, String, String)>,
+    // Row where visual-mode selection started; the range runs to the cursor
+    selection_anchor: Option<usize>,
+    diff_scroll: u16,
+    // Child indices from the roots down to every node, keyed by full path
+    node_index: HashMap<String, Vec<usize>>,
 }
 
 impl<'a> App<'a> {
     fn new(
-        items: Vec<TranslationItem>,
+        mut items: Vec<TranslationItem>,
+        key_order: KeyOrder,
         output_path: PathBuf,
-        color: bool,
+        cli: &Cli,
     ) -> Result<App<'a>, Box<dyn Error>> {
-        let translation_store = TranslationStore::new(items);
-        let mut tree = App::build_tree(translation_store.all_items.values().cloned().collect());
+        let target_lang = cli
+            .target_lang
+            .clone()
+            .or_else(|| locale::infer_locale(&output_path));
+        let plural_categories = plural_categories(cli, target_lang.as_deref());
+
+        for item in &mut items {
+            item.refresh_checks(&plural_categories);
+        }
+        let mut translation_store = TranslationStore::new(items, key_order);
+        translation_store.indent = cli
+            .indent
+            .clone()
+            .or_else(|| detect_indent(&output_path))
+            .or_else(|| detect_indent(cli.source_path()))
+            .unwrap_or_else(|| "  ".to_string());
+        translation_store.final_newline = cli.final_newline;
+        translation_store.create_dirs = cli.mkdir;
+        translation_store.format = cli.format;
+        translation_store.fill_missing = cli.fill_missing;
+        let mut tree = App::build_tree(
+            translation_store.sorted_items().cloned().collect(),
+            cli.tree_order,
+        );
         App::update_node_translation_status(&mut tree);
 
-        let clipboard: Box<dyn Clipboard> = Box::new(WaylandClipboard);
+        let reference = match &cli.reference {
+            Some(path) => TranslationStore::load_reference(path)
+                .map_err(|e| format!("Failed to load the reference file: {}", e))?,
+            None => HashMap::new(),
+        };
+        let reference_label = match cli.reference.as_deref().and_then(locale::infer_locale) {
+            Some(lang) => format!("Reference ({}): ", lang),
+            None => "Reference: ".to_string(),
+        };
+
+        let clipboard: Arc<dyn Clipboard> =
+            cli.clipboard.unwrap_or_else(ClipboardKind::detect).create();
+        let source_watcher = if cli.watch {
+            Some(SourceWatcher::new(cli.source_path())?)
+        } else {
+            None
+        };
 
         let mut app = App {
             tree,
             visible_nodes: Vec::new(),
             selected_index: 0,
             textarea: TextArea::default(),
+            history_cursor: None,
             translation_store,
             mode: AppMode::Normal,
+            source_path: cli.source_path().clone(),
+            stream: cli.stream,
+            in_place: cli.edit.is_some(),
+            // Writing to stdout is only possible once the TUI has exited
+            dry_run: cli.dry_run || cli.out.as_deref().is_some_and(is_stdio),
+            strict: cli.strict,
+            external_edit: None,
+            session: SessionState::load(&output_path),
+            notes: notes::load(&output_path)?,
+            source_hashes: if cli.edit.is_some() {
+                SourceHashes::new()
+            } else {
+                source_hashes::load(&output_path)?
+            },
+            reference,
+            reference_label,
+            mt_url: cli.mt_url.clone(),
+            patch_path: cli.patch.clone(),
+            dry_run_output: None,
             output_path,
             status_message: None,
             clipboard,
-            color,
+            clipboard_job: None,
+            color: cli.color.unwrap_or(true) && cli.theme != Some(ThemeName::Mono),
+            theme: Theme::from_name(cli.theme.unwrap_or(ThemeName::Dark)),
+            source_watcher,
+            source_changed: false,
+            scrolloff: cli.scrolloff,
+            normalize_whitespace: cli.normalize_whitespace,
+            pending_count: None,
+            search_query: None,
+            fold_accents: cli.fold_accents,
+            plural_categories,
+            length_ratio: cli.length_ratio,
+            tree_order: cli.tree_order,
+            namespace_progress: false,
+            tree_lines: cli.tree_lines,
+            ascii: cli.ascii,
+            minimap: cli.minimap,
+            readonly: cli.readonly,
+            minimap_area: Rect::default(),
+            scope: cli.namespace.clone(),
+            divergent_keys: HashSet::new(),
+            allow_divergent: cli.allow_divergent.clone(),
+            target_lang,
+            status_filter: StatusFilter::All,
+            autosave_interval: (cli.autosave > 0).then(|| Duration::from_secs(cli.autosave)),
+            last_save: Instant::now(),
+            keymap: Keymap::with_overrides(&cli.keys)?,
+            remaining_words: 0,
+            prompt_kind: None,
+            prompt_input: TextArea::default(),
+            finder_input: TextArea::default(),
+            finder_results: Vec::new(),
+            finder_selected: 0,
+            list_offset: 0,
+            key_list_area: Rect::default(),
+            last_click: None,
+            pending_action: None,
+            undo_stack: Vec::new(),
+            pending_batch: None,
+            rename_suggestions: Vec::new(),
+            selection_anchor: None,
+            diff_scroll: 0,
+            node_index: HashMap::new(),
         };
+        app.rebuild_node_index();
         app.textarea.set_block(
             Block::default()
                 .borders(Borders::ALL)
                 .title("Edit Terjemahan"),
         );
+        if let Some(scope) = &app.scope
+            && !app.node_index.contains_key(scope)
+        {
+            return Err(format!("No keys under namespace {:?}", scope).into());
+        }
         app.update_visible_nodes();
+        app.recount_remaining_words();
+        app.refresh_divergent_keys();
         Ok(app)
     }
 
-    fn save_translations(&self) -> Result<(), Box<dyn Error>> {
-        self.translation_store.save_translations(&self.output_path)
+    // Translated keys that drop a source placeholder or a required plural category.
+    fn validation_failures(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .translation_store
+            .all_items
+            .values()
+            .filter(|item| !item.valid_placeholders || !item.valid_plurals)
+            .map(|item| item.key.clone())
+            .collect();
+        keys.sort_by(|a, b| self.translation_store.key_order.compare(a, b));
+        keys
     }
 
-    fn get_translation_progress(&self) -> (usize, usize) {
-        let total_items = self.translation_store.all_items.len();
-        let translated_items = self
+    // Under `--dry-run` the output is kept in memory and printed on exit instead.
+    fn save_translations(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.strict {
+            let failures = self.validation_failures();
+            if !failures.is_empty() {
+                return Err(format!(
+                    "not saved under --strict, failing validation: {}",
+                    list_keys(&failures)
+                )
+                .into());
+            }
+        }
+        if self.dry_run {
+            let mut output = Vec::new();
+            self.translation_store
+                .write_output(&mut output, &self.output_path)?;
+            self.dry_run_output = Some(output);
+        } else {
+            self.translation_store
+                .save_translations(&self.output_path)?;
+            if let Some(patch_path) = &self.patch_path {
+                self.translation_store.save_patch(patch_path)?;
+            }
+            if !self.in_place {
+                source_hashes::update(&mut self.source_hashes, &self.translation_store);
+                source_hashes::save(&self.output_path, &self.source_hashes)?;
+            }
+        }
+        self.translation_store.mark_saved();
+        self.last_save = Instant::now();
+        Ok(())
+    }
+
+    // Re-reads the source file while keeping every in-memory target text, so
+    // unsaved edits survive the reload. Expanded folders and the selection are preserved.
+    fn reload_source(&mut self) -> Result<(), Box<dyn Error>> {
+        let (mut items, _) =
+            TranslationStore::load_from_files(&self.source_path, None, None, self.stream, false)?;
+        for item in items.iter_mut() {
+            if let Some(existing) = self.translation_store.all_items.get(&item.key) {
+                item.target_text = existing.target_text.clone();
+            }
+            item.refresh_checks(&self.plural_categories);
+        }
+
+        let mut expanded_paths = Vec::new();
+        Self::collect_expanded_paths(&self.tree, &mut expanded_paths);
+        let selected_path = self.get_selected_path();
+
+        let saved_targets = std::mem::take(&mut self.translation_store.saved_targets);
+        let loaded_targets = std::mem::take(&mut self.translation_store.loaded_targets);
+        let key_order = KeyOrder::new(self.translation_store.key_order.sort, &items);
+        let indent = std::mem::take(&mut self.translation_store.indent);
+        let final_newline = self.translation_store.final_newline;
+        let create_dirs = self.translation_store.create_dirs;
+        let format = self.translation_store.format;
+        let fill_missing = self.translation_store.fill_missing;
+        self.translation_store = TranslationStore::new(items, key_order);
+        self.translation_store.indent = indent;
+        self.translation_store.final_newline = final_newline;
+        self.translation_store.create_dirs = create_dirs;
+        self.translation_store.format = format;
+        self.translation_store.fill_missing = fill_missing;
+        self.translation_store.saved_targets = saved_targets;
+        self.translation_store.loaded_targets = loaded_targets;
+        self.tree = App::build_tree(
+            self.translation_store.sorted_items().cloned().collect(),
+            self.tree_order,
+        );
+        App::update_node_translation_status(&mut self.tree);
+        self.rebuild_node_index();
+        self.recount_remaining_words();
+        self.refresh_divergent_keys();
+        for path in &expanded_paths {
+            if let Some(node) = self.get_node_mut(path) {
+                node.expanded = true;
+            }
+        }
+        self.update_visible_nodes();
+        if let Some(path) = selected_path
+            && let Some(index) = self.visible_nodes.iter().position(|(p, _)| p == &path)
+        {
+            self.selected_index = index;
+        }
+        self.source_changed = false;
+        Ok(())
+    }
+
+    fn collect_expanded_paths(nodes: &[TreeNode], paths: &mut Vec<String>) {
+        for node in nodes {
+            if node.expanded {
+                paths.push(node.full_path.clone());
+                Self::collect_expanded_paths(&node.children, paths);
+            }
+        }
+    }
+
+    fn recount_remaining_words(&mut self) {
+        self.remaining_words = self
             .translation_store
             .all_items
             .values()
-            .filter(|item| item.is_translated())
-            .count();
-        (translated_items, total_items)
+            .filter(|item| !item.is_translated() && self.in_scope(&item.key))
+            .map(|item| item.source_text.split_whitespace().count())
+            .sum();
     }
 
-    fn build_tree(items: Vec<TranslationItem>) -> Vec<TreeNode> {
-        let mut root_nodes: Vec<TreeNode> = Vec::new();
+    // Under `--edit` the sources are the texts as loaded, so comparing
+    // translations of the same source says nothing.
+    fn refresh_divergent_keys(&mut self) {
+        if self.in_place {
+            return;
+        }
+        self.divergent_keys = divergent_groups(
+            self.translation_store.all_items.values(),
+            &self.allow_divergent,
+        )
+        .into_iter()
+        .flatten()
+        .map(|item| item.key.clone())
+        .collect();
+    }
+
+    // Copies the selected key's translation to every other key with the same
+    // source text, as one undoable step.
+    fn unify_divergent(&mut self) {
+        let Some(path) = self.get_selected_path() else {
+            return;
+        };
+        let Some(selected) = self.translation_store.all_items.get(&path) else {
+            return;
+        };
+        if !self.divergent_keys.contains(&path) {
+            self.status_message = Some((
+                "This key has no diverging duplicates.".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        let changes: ChangeBatch = self
+            .translation_store
+            .all_items
+            .values()
+            .filter(|item| {
+                item.key != path
+                    && item.source_text == selected.source_text
+                    && item.target_text != selected.target_text
+                    && !self.allow_divergent.contains(&item.key)
+            })
+            .map(|item| {
+                (
+                    item.key.clone(),
+                    selected.target_text.clone(),
+                    item.needs_review,
+                )
+            })
+            .collect();
+        let count = changes.len();
+        self.apply_changes(changes);
+        self.status_message = Some((
+            format!("Copied this translation to {} keys (u to undo)", count),
+            Instant::now(),
+        ));
+    }
+
+    fn get_translation_progress(&self) -> (usize, usize) {
+        self.translation_store
+            .all_items
+            .values()
+            .filter(|item| self.in_scope(&item.key))
+            .fold((0, 0), |(translated, total), item| {
+                (translated + item.is_translated() as usize, total + 1)
+            })
+    }
 
-        let mut sorted_items = items;
-        sorted_items.sort_by(|a, b| a.key.cmp(&b.key));
+    // Nests `items`, which come in key order, so every folder's children are
+    // in that order as well.
+    fn build_tree(items: Vec<TranslationItem>, order: TreeOrder) -> Vec<TreeNode> {
+        let mut root_nodes: Vec<TreeNode> = Vec::new();
 
-        for item in sorted_items {
+        for item in items {
             let segments: Vec<&str> = item.key.split('.').collect();
             let mut current_level_nodes = &mut root_nodes;
             let mut path_so_far = String::new();
@@ -153,6 +940,8 @@ impl<'a> App<'a> {
                                     children: Vec::new(),
                                     expanded: false,
                                     fully_translated: false,
+                                    progress: (0, 0),
+                                    revealed: FOLDER_PAGE_SIZE,
                                 };
                                 nodes.push(new_node);
                                 nodes.len() - 1
@@ -170,80 +959,257 @@ impl<'a> App<'a> {
                 current_level_nodes = &mut current_level_nodes[node_index].children;
             }
         }
+        if order != TreeOrder::Alpha {
+            Self::group_siblings(&mut root_nodes, order);
+        }
         root_nodes
     }
 
-    fn update_node_translation_status(nodes: &mut [TreeNode]) -> bool {
-        let mut all_children_translated = true;
+    // Moves folders before leaves or the other way round; the sort is stable, so
+    // key order is kept within each group.
+    fn group_siblings(nodes: &mut [TreeNode], order: TreeOrder) {
+        nodes.sort_by_key(|node| (order == TreeOrder::FoldersFirst) == node.is_leaf());
+        for node in nodes {
+            Self::group_siblings(&mut node.children, order);
+        }
+    }
+
+    // Refreshes `fully_translated` and `progress` on every node and returns the
+    // (translated, total) leaf counts over `nodes`.
+    fn update_node_translation_status(nodes: &mut [TreeNode]) -> (usize, usize) {
+        let mut total_progress = (0, 0);
         for node in nodes.iter_mut() {
-            if node.is_leaf() {
-                node.fully_translated = node
-                    .translation
-                    .as_ref()
-                    .map_or(false, |t| t.is_translated());
+            node.progress = if node.is_leaf() {
+                let translated = node.translation.as_ref().is_some_and(|t| t.is_translated());
+                (translated as usize, 1)
             } else {
-                let children_translated = Self::update_node_translation_status(&mut node.children);
-                node.fully_translated = children_translated;
-            }
-            if !node.fully_translated {
-                all_children_translated = false;
-            }
+                Self::update_node_translation_status(&mut node.children)
+            };
+            node.fully_translated = node.progress.0 == node.progress.1;
+            total_progress.0 += node.progress.0;
+            total_progress.1 += node.progress.1;
         }
-        all_children_translated
+        total_progress
     }
 
     fn update_visible_nodes(&mut self) {
         self.visible_nodes.clear();
-        Self::generate_visible_list_recursive(&self.tree, 0, &mut self.visible_nodes);
+        let fold_accents = self.fold_accents;
+        let query = self
+            .search_query
+            .as_ref()
+            .map(|q| fold_for_search(q, fold_accents).0);
+        let status_filter = self.status_filter;
+        let leaf_filter = |node: &TreeNode| {
+            let matches_query = match &query {
+                Some(q) => fold_for_search(&node.full_path, fold_accents).0.contains(q),
+                None => true,
+            };
+            matches_query && status_filter.accepts(node)
+        };
+        // While filtering, folders open up so every match is reachable
+        let force_expand = query.is_some() || status_filter != StatusFilter::All;
+        let mut visible_nodes = std::mem::take(&mut self.visible_nodes);
+        Self::generate_visible_list_recursive(
+            self.scope_roots(),
+            0,
+            &leaf_filter,
+            force_expand,
+            &mut visible_nodes,
+        );
+        self.visible_nodes = visible_nodes;
         if self.selected_index >= self.visible_nodes.len() && !self.visible_nodes.is_empty() {
             self.selected_index = self.visible_nodes.len() - 1;
         }
     }
 
+    // The top level of the key list: the scoped folder's children, or the whole tree.
+    fn scope_roots(&self) -> &[TreeNode] {
+        match self.scope.as_deref().and_then(|scope| self.get_node(scope)) {
+            Some(node) if node.is_leaf() => std::slice::from_ref(node),
+            Some(node) => &node.children,
+            None => &self.tree,
+        }
+    }
+
+    fn in_scope(&self, key: &str) -> bool {
+        match &self.scope {
+            Some(scope) => key
+                .strip_prefix(scope.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.')),
+            None => true,
+        }
+    }
+
+    // Scopes the tree to the selected folder (or a leaf's folder), or back to
+    // the whole tree when already scoped.
+    fn toggle_scope(&mut self) {
+        if self.scope.take().is_none() {
+            let Some(node) = self.get_selected_node() else {
+                return;
+            };
+            self.scope = if node.is_leaf() {
+                node.full_path
+                    .rsplit_once('.')
+                    .map(|(parent, _)| parent.to_string())
+            } else {
+                Some(node.full_path.clone())
+            };
+        }
+        let selected_path = self.get_selected_path();
+        self.update_visible_nodes();
+        self.recount_remaining_words();
+        match selected_path {
+            Some(path) if self.in_scope(&path) => self.reveal_path(&path),
+            _ => self.selected_index = 0,
+        }
+    }
+
     fn generate_visible_list_recursive(
         nodes: &[TreeNode],
         depth: usize,
+        leaf_filter: &dyn Fn(&TreeNode) -> bool,
+        force_expand: bool,
         visible_list: &mut Vec<(String, usize)>,
     ) {
         for node in nodes {
+            if !Self::subtree_matches(node, leaf_filter) {
+                continue;
+            }
             visible_list.push((node.full_path.clone(), depth));
-            if node.expanded {
-                Self::generate_visible_list_recursive(&node.children, depth + 1, visible_list);
+            if node.expanded || force_expand {
+                // Filtered lists are short enough already
+                let shown = if force_expand {
+                    node.children.len()
+                } else {
+                    node.revealed.min(node.children.len())
+                };
+                Self::generate_visible_list_recursive(
+                    &node.children[..shown],
+                    depth + 1,
+                    leaf_filter,
+                    force_expand,
+                    visible_list,
+                );
+                if shown < node.children.len() {
+                    visible_list
+                        .push((format!("{}{}", node.full_path, MORE_ROW_SUFFIX), depth + 1));
+                }
             }
         }
     }
 
-    fn get_node(&self, path: &str) -> Option<&TreeNode> {
-        let mut segments = path.split('.');
-        let root_segment = segments.next()?;
-        let mut current_node = self.tree.iter().find(|n| n.key_segment == root_segment)?;
-        for segment in segments {
-            current_node = current_node
-                .children
+    // A folder is shown when any leaf below it passes the filter.
+    fn subtree_matches(node: &TreeNode, leaf_filter: &dyn Fn(&TreeNode) -> bool) -> bool {
+        if node.is_leaf() {
+            leaf_filter(node)
+        } else {
+            node.children
                 .iter()
-                .find(|n| n.key_segment == segment)?;
+                .any(|child| Self::subtree_matches(child, leaf_filter))
+        }
+    }
+
+    fn set_search(&mut self, query: Option<String>) {
+        self.search_query = query.filter(|q| !q.is_empty());
+        self.refresh_filtered_view();
+    }
+
+    // Pressing the same filter twice turns it back off.
+    fn toggle_status_filter(&mut self, filter: StatusFilter) {
+        self.status_filter = if self.status_filter == filter {
+            StatusFilter::All
+        } else {
+            filter
+        };
+        self.refresh_filtered_view();
+    }
+
+    // Rebuilds the visible list after a filter change, keeping the selection
+    // on the same key when it is still shown.
+    fn refresh_filtered_view(&mut self) {
+        let selected_path = self.get_selected_path();
+        self.update_visible_nodes();
+        if let Some(path) = selected_path
+            && let Some(index) = self.visible_nodes.iter().position(|(p, _)| p == &path)
+        {
+            self.selected_index = index;
+        }
+    }
+
+    // Splits a key segment into spans, emphasizing the part matching the search.
+    fn highlighted_segment(&self, segment: &str) -> Vec<Span<'static>> {
+        let match_range = self.search_query.as_ref().and_then(|query| {
+            let (folded, offsets) = fold_for_search(segment, self.fold_accents);
+            let needle = fold_for_search(query, self.fold_accents).0;
+            if needle.is_empty() {
+                return None;
+            }
+            let start = folded.find(&needle)?;
+            // Map back to the original text, ending after the character that
+            // produced the last matched byte
+            let last = offsets[start + needle.len() - 1];
+            let last_len = segment[last..].chars().next().map_or(0, char::len_utf8);
+            Some(offsets[start]..last + last_len)
+        });
+        let Some(range) = match_range else {
+            return vec![Span::raw(segment.to_string())];
+        };
+
+        let match_style = if self.color {
+            Style::default()
+                .fg(self.theme.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        };
+        vec![
+            Span::raw(segment[..range.start].to_string()),
+            Span::styled(segment[range.clone()].to_string(), match_style),
+            Span::raw(segment[range.end..].to_string()),
+        ]
+    }
+
+    // Must run whenever the tree's structure changes; expanding or editing
+    // nodes in place keeps the index valid.
+    fn rebuild_node_index(&mut self) {
+        self.node_index.clear();
+        Self::index_nodes(&self.tree, &mut Vec::new(), &mut self.node_index);
+    }
+
+    fn index_nodes(
+        nodes: &[TreeNode],
+        indices: &mut Vec<usize>,
+        index: &mut HashMap<String, Vec<usize>>,
+    ) {
+        for (i, node) in nodes.iter().enumerate() {
+            indices.push(i);
+            index.insert(node.full_path.clone(), indices.clone());
+            Self::index_nodes(&node.children, indices, index);
+            indices.pop();
+        }
+    }
+
+    fn get_node(&self, path: &str) -> Option<&TreeNode> {
+        let (first, rest) = self.node_index.get(path)?.split_first()?;
+        let mut current_node = self.tree.get(*first)?;
+        for i in rest {
+            current_node = current_node.children.get(*i)?;
         }
         Some(current_node)
     }
 
     fn get_node_mut(&mut self, path: &str) -> Option<&mut TreeNode> {
-        let mut segments = path.split('.');
-        let root_segment = segments.next()?;
-        let mut current_node = self
-            .tree
-            .iter_mut()
-            .find(|n| n.key_segment == root_segment)?;
-        for segment in segments {
-            current_node = current_node
-                .children
-                .iter_mut()
-                .find(|n| n.key_segment == segment)?;
+        let (first, rest) = self.node_index.get(path)?.split_first()?;
+        let mut current_node = self.tree.get_mut(*first)?;
+        for i in rest {
+            current_node = current_node.children.get_mut(*i)?;
         }
         Some(current_node)
     }
 
     fn next(&mut self) {
-        if self.selected_index < self.visible_nodes.len() - 1 {
+        if self.selected_index + 1 < self.visible_nodes.len() {
             self.selected_index += 1;
         }
     }
@@ -254,14 +1220,24 @@ impl<'a> App<'a> {
         }
     }
 
+    fn next_by(&mut self, steps: usize) {
+        let last = self.visible_nodes.len().saturating_sub(1);
+        self.selected_index = self.selected_index.saturating_add(steps).min(last);
+    }
+
+    fn previous_by(&mut self, steps: usize) {
+        self.selected_index = self.selected_index.saturating_sub(steps);
+    }
+
     fn toggle_expand(&mut self) {
         // Get the path of the currently selected node before any changes.
-        if let Some((path_before_toggle, _)) = self.visible_nodes.get(self.selected_index).cloned() {
+        if let Some((path_before_toggle, _)) = self.visible_nodes.get(self.selected_index).cloned()
+        {
             // Find the node in the tree and toggle its expanded state.
-            if let Some(node) = self.get_node_mut(&path_before_toggle) {
-                if !node.is_leaf() {
-                    node.expanded = !node.expanded;
-                }
+            if let Some(node) = self.get_node_mut(&path_before_toggle)
+                && !node.is_leaf()
+            {
+                node.expanded = !node.expanded;
             }
 
             // Regenerate the list of visible nodes.
@@ -281,222 +1257,1811 @@ impl<'a> App<'a> {
         }
     }
 
-    fn render_key_list(&self, f: &mut Frame, area: Rect) {
-        let list_style = if self.color && matches!(self.mode, AppMode::Normal) {
-            Style::default()
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+    fn editor_style(&self) -> Style {
+        if self.color {
+            Style::default().fg(self.theme.accent)
         } else {
             Style::default()
-        };
-
-        let items: Vec<ListItem> = self
-            .visible_nodes
-            .iter()
-            .map(|(path, depth)| {
-                let node = self.get_node(path).unwrap(); // Should exist
-                let is_leaf = node.is_leaf();
-
-                let status_span = if is_leaf {
-                    if node
-                        .translation
-                        .as_ref()
-                        .map_or(false, |t| t.is_translated())
-                    {
-                        if self.color {
-                            Span::styled(
-                                "[✓]",
-                                Style::default()
-                                    .fg(Color::Green)
-                                    .add_modifier(Modifier::BOLD),
-                            )
-                        } else {
-                            Span::raw("[✓]")
-                        }
-                    } else {
-                        if self.color {
-                            Span::styled("[ ]", Style::default().fg(Color::LightRed))
-                        } else {
-                            Span::raw("[ ]")
-                        }
-                    }
-                } else {
-                    // It's a folder
-                    if node.fully_translated {
-                        if self.color {
-                            Span::styled(
-                                "[✓]",
-                                Style::default()
-                                    .fg(Color::Green)
-                                    .add_modifier(Modifier::BOLD),
-                            )
-                        } else {
-                            Span::raw("[✓]")
-                        }
-                    } else if node.expanded {
-                        if self.color {
-                            Span::styled("[-] ", Style::default().fg(Color::Blue))
-                        } else {
-                            Span::raw("[-] ")
-                        }
-                    } else {
-                        if self.color {
-                            Span::styled("[+] ", Style::default().fg(Color::LightCyan))
-                        } else {
-                            Span::raw("[+] ")
-                        }
-                    }
-                };
-
-                let indentation = "  ".repeat(*depth);
-
-                let line = Line::from(vec![
-                    Span::raw(indentation),
-                    status_span,
-                    Span::raw(node.key_segment.clone()),
-                ]);
-
-                ListItem::new(line)
-            })
-            .collect();
-
-        let items_list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Daftar Kunci"))
-            .highlight_style(list_style)
-            .highlight_symbol(">> ");
+        }
+    }
 
-        let mut list_state = ratatui::widgets::ListState::default();
-        list_state.select(Some(self.selected_index));
+    // Styles a span only when colors are enabled.
+    fn styled(&self, text: impl Into<String>, style: Style) -> Span<'static> {
+        if self.color {
+            Span::styled(text.into(), style)
+        } else {
+            Span::raw(text.into())
+        }
+    }
 
-        // --- NEW "STAY-CENTERED" LOGIC ---
-        let height = area.height as usize;
-        let num_items = self.visible_nodes.len();
+    // Pads the status marker to a fixed number of terminal cells so key names
+    // line up whatever the marker's glyphs are.
+    fn node_status_span(&self, node: &TreeNode) -> Span<'static> {
+        let (label, style) = self.node_status_label(node);
+        let label = if self.ascii {
+            ascii_marker(label)
+        } else {
+            label
+        };
+        self.styled(pad_to_width(label, STATUS_COLUMN_WIDTH), style)
+    }
 
-        if height > 1 && num_items > 0 {
-            // 1. Calculate the desired position for the cursor (the middle row).
-            let middle_row = height / 2;
+    // A saved translation whose source has changed since it was saved. Never
+    // under `--edit`, where saving changes the source along with it.
+    fn is_stale(&self, item: &TranslationItem) -> bool {
+        !self.in_place
+            && item.is_translated()
+            && !self.translation_store.is_modified(&item.key)
+            && self
+                .source_hashes
+                .get(&item.key)
+                .is_some_and(|saved| *saved != source_hashes::hash(&item.source_text))
+    }
 
-            // 2. Calculate the ideal offset to place the selected item in the middle.
-            //    If selected_index is 10 and middle_row is 5, offset should be 5,
-            //    so item 5 is at the top and item 10 is in the middle.
-            let mut offset = self.selected_index.saturating_sub(middle_row);
+    fn node_status_label(&self, node: &TreeNode) -> (&'static str, Style) {
+        let done_style = Style::default()
+            .fg(self.theme.translated)
+            .add_modifier(Modifier::BOLD);
+        let untranslated_style = Style::default().fg(self.theme.untranslated);
 
-            // 3. Clamp the offset to prevent scrolling past the beginning or end.
-            if num_items > height {
-                let max_offset = num_items - height;
-                offset = offset.min(max_offset);
+        if let Some(item) = node.translation.as_ref().filter(|_| node.is_leaf()) {
+            if item.needs_review {
+                ("[?]", Style::default().fg(self.theme.needs_review))
+            } else if self.is_stale(item) {
+                ("[↻]", Style::default().fg(self.theme.needs_review))
+            } else if item.target_text.as_deref() == Some("") {
+                // Deliberately left empty, unlike a key the target file lacks
+                ("[∅]", Style::default().fg(self.theme.muted))
+            } else if !item.valid_plurals {
+                ("[#]", untranslated_style)
+            } else if !item.valid_placeholders {
+                ("[⚠]", Style::default().fg(self.theme.needs_review))
+            } else if self.divergent_keys.contains(&item.key) {
+                ("[≠]", Style::default().fg(self.theme.needs_review))
+            } else if !item.valid_whitespace {
+                ("[_]", Style::default().fg(self.theme.needs_review))
+            } else if item.exceeds_length_ratio(self.length_ratio) {
+                ("[!]", Style::default().fg(self.theme.needs_review))
+            } else if !self.in_place && item.is_identical_to_source(self.normalize_whitespace) {
+                // Under `--edit` every untouched text is its own source
+                ("[=]", Style::default().fg(self.theme.identical))
+            } else if item.is_translated() {
+                ("[✓]", done_style)
             } else {
-                // If all items fit on the screen, no scrolling is needed.
-                offset = 0;
+                ("[ ]", untranslated_style)
+            }
+        } else if node.is_leaf() {
+            ("[ ]", untranslated_style)
+        } else {
+            // It's a folder
+            if node.fully_translated {
+                ("[✓]", done_style)
+            } else if node.expanded {
+                ("[-]", Style::default().fg(self.theme.folder_expanded))
+            } else {
+                ("[+]", Style::default().fg(self.theme.folder_collapsed))
             }
-
-            *list_state.offset_mut() = offset;
         }
-        // --- END OF NEW LOGIC ---
-
-        f.render_stateful_widget(items_list, area, &mut list_state);
     }
 
-    fn render_source_text(&self, f: &mut Frame, area: Rect) {
-        let (source_text, target_display_text) =
+    // Expands every folder shallower than `depth` and collapses the rest, then
+    // selects the nearest visible ancestor of the previously selected key.
+    fn expand_to_depth(&mut self, depth: usize) {
+        let selected_path = self.get_selected_path();
+        Self::set_expanded_to_depth(&mut self.tree, 0, depth);
+        self.update_visible_nodes();
+
+        if let Some(path) = selected_path {
+            let segments: Vec<&str> = path.split('.').collect();
+            for len in (1..=segments.len()).rev() {
+                let ancestor = segments[..len].join(".");
+                if let Some(index) = self.visible_nodes.iter().position(|(p, _)| p == &ancestor) {
+                    self.selected_index = index;
+                    break;
+                }
+            }
+        }
+    }
+
+    // Expands the selected folder and every folder below it, or collapses them all
+    // if it was expanded. The rest of the tree is left as it is.
+    fn toggle_subtree(&mut self) {
+        let Some(path) = self.get_selected_path() else {
+            return;
+        };
+        if let Some(node) = self.get_node_mut(&path)
+            && !node.is_leaf()
+        {
+            let expanded = !node.expanded;
+            Self::set_subtree_expanded(node, expanded);
+        }
+        self.update_visible_nodes();
+        if let Some(index) = self.visible_nodes.iter().position(|(p, _)| p == &path) {
+            self.selected_index = index;
+        }
+    }
+
+    fn set_subtree_expanded(node: &mut TreeNode, expanded: bool) {
+        if !node.is_leaf() {
+            node.expanded = expanded;
+            for child in node.children.iter_mut() {
+                Self::set_subtree_expanded(child, expanded);
+            }
+        }
+    }
+
+    fn set_expanded_to_depth(nodes: &mut [TreeNode], current_depth: usize, depth: usize) {
+        for node in nodes.iter_mut() {
+            if !node.is_leaf() {
+                node.expanded = current_depth < depth;
+                Self::set_expanded_to_depth(&mut node.children, current_depth + 1, depth);
+            }
+        }
+    }
+
+    // Box-drawing prefixes for every visible row. Scanning upwards, `continues[d]`
+    // records whether a later sibling exists at depth `d` below the current row.
+    fn tree_connectors(&self) -> Vec<String> {
+        let (through, branch, last) = if self.ascii {
+            ("| ", "|-", "`-")
+        } else {
+            ("│ ", "├─", "└─")
+        };
+        let mut continues: Vec<bool> = Vec::new();
+        let mut connectors = vec![String::new(); self.visible_nodes.len()];
+        for (i, (_, depth)) in self.visible_nodes.iter().enumerate().rev() {
+            let depth = *depth;
+            continues.resize(depth + 1, false);
+            let mut prefix: String = continues[..depth]
+                .iter()
+                .map(|&more| if more { through } else { "  " })
+                .collect();
+            prefix.push_str(if continues[depth] { branch } else { last });
+            // Pad by display width, not chars, so the status icons line up
+            connectors[i] = pad_to_width(&prefix, (depth + 1) * 2);
+            continues[depth] = true;
+        }
+        connectors
+    }
+
+    // Visible rows split into `cells` consecutive chunks, as index ranges.
+    fn minimap_chunks(&self, cells: usize) -> Vec<std::ops::Range<usize>> {
+        let rows = self.visible_nodes.len();
+        let cells = cells.min(rows);
+        (0..cells)
+            .map(|i| i * rows / cells..(i + 1) * rows / cells)
+            .collect()
+    }
+
+    // One cell per chunk of the visible rows, shaded by the share of its keys
+    // that are translated. Collapsed folders count all the keys inside them.
+    fn render_minimap(&mut self, f: &mut Frame, area: Rect) {
+        // Line the cells up with the list's rows, inside its borders
+        let area = Rect {
+            y: area.y + 1,
+            height: area.height.saturating_sub(2),
+            ..area
+        };
+        self.minimap_area = area;
+        let lines: Vec<Line> = self
+            .minimap_chunks(area.height as usize)
+            .into_iter()
+            .map(|chunk| {
+                let (translated, total) = self.visible_nodes[chunk]
+                    .iter()
+                    .filter_map(|(path, _)| self.get_node(path))
+                    .filter(|node| node.is_leaf() || !node.expanded)
+                    .fold((0, 0), |(done, all), node| {
+                        (done + node.progress.0, all + node.progress.1)
+                    });
+                let (glyph, color) = if total == 0 {
+                    (" ", self.theme.muted)
+                } else if translated == total {
+                    (if self.ascii { "#" } else { "█" }, self.theme.translated)
+                } else if translated == 0 {
+                    (if self.ascii { "." } else { "░" }, self.theme.untranslated)
+                } else {
+                    (if self.ascii { "+" } else { "▒" }, self.theme.needs_review)
+                };
+                Line::from(self.styled(glyph, Style::default().fg(color)))
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), area);
+    }
+
+    fn render_key_list(&mut self, f: &mut Frame, area: Rect) {
+        let area = if self.minimap && area.width > 2 {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(area);
+            self.render_minimap(f, chunks[1]);
+            chunks[0]
+        } else {
+            self.minimap_area = Rect::default();
+            area
+        };
+        let list_style = if self.color && matches!(self.mode, AppMode::Normal) {
+            Style::default()
+                .bg(self.theme.selection)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default()
+        };
+
+        let connectors = self.tree_lines.then(|| self.tree_connectors());
+        let selected_rows = self.selected_range();
+        let selected_row_style = if self.color {
+            Style::default().bg(self.theme.muted)
+        } else {
+            Style::default().add_modifier(Modifier::UNDERLINED)
+        };
+        let items: Vec<ListItem> = self
+            .visible_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (path, depth))| {
+                let indentation = match &connectors {
+                    Some(connectors) => connectors[i].clone(),
+                    None => "  ".repeat(*depth),
+                };
+
+                if let Some(parent) = path.strip_suffix(MORE_ROW_SUFFIX)
+                    && let Some(parent) = self.get_node(parent)
+                {
+                    let more = format!(
+                        "{} {} more",
+                        if self.ascii { "..." } else { "…" },
+                        parent.children.len().saturating_sub(parent.revealed)
+                    );
+                    return ListItem::new(Line::from(vec![
+                        Span::raw(indentation),
+                        self.styled(more, Style::default().fg(self.theme.muted)),
+                    ]));
+                }
+
+                let node = self.get_node(path).unwrap(); // Should exist
+                let status_span = self.node_status_span(node);
+
+                let mut spans = vec![Span::raw(indentation), status_span];
+                spans.extend(self.highlighted_segment(&node.key_segment));
+                // Unsaved edits are marked like a modified editor tab; bookmarks
+                // get a different glyph so the two can't be mistaken without color
+                if node.is_leaf() && self.translation_store.is_modified(path) {
+                    let dirty = if self.ascii { " +" } else { " ●" };
+                    spans.push(self.styled(dirty, Style::default().fg(self.theme.added)));
+                }
+                if self.session.bookmarks.contains(path) {
+                    let bookmark = if self.ascii { " *" } else { " ★" };
+                    spans.push(self.styled(bookmark, Style::default().fg(self.theme.accent)));
+                }
+                let line = Line::from(spans);
+
+                if selected_rows.as_ref().is_some_and(|rows| rows.contains(&i)) {
+                    return ListItem::new(line).style(selected_row_style);
+                }
+                ListItem::new(line)
+            })
+            .collect();
+
+        let items_list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Daftar Kunci"))
+            .highlight_style(list_style)
+            .highlight_symbol(">> ");
+
+        let mut list_state = ratatui::widgets::ListState::default();
+        list_state.select(Some(self.selected_index));
+
+        // Rows available inside the list's borders
+        let height = area.height.saturating_sub(2) as usize;
+        let num_items = self.visible_nodes.len();
+
+        if height > 1 && num_items > 0 {
+            let middle_row = height / 2;
+            let mut offset = match self.scrolloff {
+                // A margin that fits on screen: only scroll when the cursor
+                // comes within `margin` rows of the top or bottom edge.
+                Some(margin) if margin < middle_row => {
+                    let mut offset = self.list_offset;
+                    if self.selected_index < offset + margin {
+                        offset = self.selected_index.saturating_sub(margin);
+                    }
+                    if self.selected_index + margin >= offset + height {
+                        offset = self.selected_index + margin + 1 - height;
+                    }
+                    offset
+                }
+                // Otherwise keep the selected item in the middle row.
+                _ => self.selected_index.saturating_sub(middle_row),
+            };
+
+            // Clamp the offset to prevent scrolling past the beginning or end.
+            if num_items > height {
+                let max_offset = num_items - height;
+                offset = offset.min(max_offset);
+            } else {
+                // If all items fit on the screen, no scrolling is needed.
+                offset = 0;
+            }
+
+            self.list_offset = offset;
+            *list_state.offset_mut() = offset;
+        }
+        self.key_list_area = area;
+
+        f.render_stateful_widget(items_list, area, &mut list_state);
+    }
+
+    fn render_breadcrumb(&self, f: &mut Frame, area: Rect) {
+        let Some(path) = self.get_selected_path() else {
+            return;
+        };
+        let separator_style = if self.color {
+            Style::default().fg(self.theme.muted)
+        } else {
+            Style::default()
+        };
+
+        let segments: Vec<&str> = path.split('.').collect();
+        let mut spans = Vec::new();
+        for (i, segment) in segments.iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::styled(" › ", separator_style));
+            }
+            if i == segments.len() - 1 {
+                spans.push(Span::styled(
+                    segment.to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ));
+            } else {
+                spans.push(Span::raw(segment.to_string()));
+            }
+        }
+        f.render_widget(Paragraph::new(Line::from(spans)), area);
+    }
+
+    fn render_source_text(&self, f: &mut Frame, area: Rect) {
+        let note = self
+            .get_selected_path()
+            .and_then(|path| self.notes.get(&path));
+        let (source_text, target_display_text, context) =
             if let Some((path, _)) = self.visible_nodes.get(self.selected_index) {
                 if let Some(item) = self.translation_store.all_items.get(path) {
-                    (item.source_text.clone(), item.get_display_text())
+                    (
+                        item.source_text.clone(),
+                        item.get_display_text(),
+                        item.context.clone(),
+                    )
                 } else {
-                    ("Select a translatable key.".to_string(), String::new())
+                    (
+                        "Select a translatable key.".to_string(),
+                        String::new(),
+                        None,
+                    )
                 }
             } else {
-                (String::new(), String::new())
+                (String::new(), String::new(), None)
             };
 
-        let mut text_lines = vec![Line::from(vec![
-            Span::styled("Source: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(source_text),
-        ])];
+        // While editing, the target is in the editor below; flag the source
+        // placeholders the draft doesn't contain yet instead
+        let mut title = "Teks Sumber".to_string();
+        let mut missing = Vec::new();
+        if self.mode == AppMode::Editing {
+            let draft = self.textarea.lines().join("\n");
+            missing = extract_placeholders(&source_text)
+                .into_iter()
+                .filter(|p| !draft.contains(p.as_str()))
+                .collect();
+            if !missing.is_empty() {
+                title = format!("Teks Sumber ({} placeholder(s) missing)", missing.len());
+            }
+        }
+        let missing_style = if self.color {
+            Style::default()
+                .fg(self.theme.untranslated)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+        };
+
+        let mut text_lines = Self::labeled_lines("Source: ", &source_text, &missing, missing_style);
+
+        if let Some(reference) = self
+            .get_selected_path()
+            .and_then(|path| self.reference.get(&path))
+        {
+            text_lines.extend(Self::labeled_lines(
+                &self.reference_label,
+                reference,
+                &[],
+                Style::default(),
+            ));
+        }
+
+        if let Some(context) = &context {
+            text_lines.extend(Self::labeled_lines(
+                "Context: ",
+                context,
+                &[],
+                Style::default(),
+            ));
+        }
+
+        if let Some(note) = note {
+            text_lines.extend(Self::labeled_lines("Note: ", note, &[], Style::default()));
+        }
 
-        if !target_display_text.is_empty() {
-            text_lines.push(Line::from(vec![
-                Span::styled("Target: ", Style::default().add_modifier(Modifier::BOLD)),
-                Span::raw(target_display_text),
+        if !target_display_text.is_empty() && self.mode != AppMode::Editing {
+            text_lines.extend(Self::labeled_lines(
+                "Target: ",
+                &target_display_text,
+                &[],
+                Style::default(),
+            ));
+        }
+
+        let mut source_paragraph =
+            Paragraph::new(text_lines).block(Block::default().borders(Borders::ALL).title(title));
+        if self.session.wrap_source {
+            source_paragraph = source_paragraph.wrap(Wrap { trim: false });
+        }
+        f.render_widget(source_paragraph, area);
+    }
+
+    // Splits multi-line text into separate `Line`s, putting the bold label on the first one
+    // and styling every occurrence of the `highlight` tokens.
+    fn labeled_lines(
+        label: &str,
+        text: &str,
+        highlight: &[String],
+        highlight_style: Style,
+    ) -> Vec<Line<'static>> {
+        text.split('\n')
+            .enumerate()
+            .map(|(i, line)| {
+                let mut spans = Self::highlight_tokens(line, highlight, highlight_style);
+                if i == 0 {
+                    spans.insert(
+                        0,
+                        Span::styled(
+                            label.to_string(),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                    );
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    fn highlight_tokens(line: &str, tokens: &[String], style: Style) -> Vec<Span<'static>> {
+        let mut ranges: Vec<(usize, usize)> = tokens
+            .iter()
+            .flat_map(|token| {
+                line.match_indices(token.as_str())
+                    .map(|(start, m)| (start, start + m.len()))
+            })
+            .collect();
+        ranges.sort();
+
+        let mut spans = Vec::new();
+        let mut position = 0;
+        for (start, end) in ranges {
+            // Skip matches overlapping one already highlighted
+            if start < position {
+                continue;
+            }
+            if start > position {
+                spans.push(Span::raw(line[position..start].to_string()));
+            }
+            spans.push(Span::styled(line[start..end].to_string(), style));
+            position = end;
+        }
+        if position < line.len() || spans.is_empty() {
+            spans.push(Span::raw(line[position..].to_string()));
+        }
+        spans
+    }
+
+    fn render_editor(&mut self, f: &mut Frame, area: Rect) {
+        if self.mode == AppMode::Editing {
+            let source_text = self
+                .get_selected_path()
+                .and_then(|path| self.translation_store.all_items.get(&path))
+                .map(|item| item.source_text.clone())
+                .unwrap_or_default();
+            let (chars, words) = text_counts(&self.textarea.lines().join("\n"));
+            let (source_chars, source_words) = text_counts(&source_text);
+            let placeholder_hints: Vec<String> = extract_placeholders(&source_text)
+                .iter()
+                .take(9)
+                .enumerate()
+                .map(|(i, p)| format!("Alt+{} {}", i + 1, p))
+                .collect();
+            let mut block = Block::default()
+                .borders(Borders::ALL)
+                .title("Edit Terjemahan (Ctrl+q to save, Tab to save & edit the next untranslated, Esc to cancel)")
+                .title_bottom(format!(
+                    " {} chars, {} words | source: {} chars, {} words ",
+                    chars, words, source_chars, source_words
+                ))
+                .style(self.editor_style());
+            if !placeholder_hints.is_empty() {
+                block = block.title_bottom(
+                    Line::from(format!(" {} ", placeholder_hints.join("  "))).right_aligned(),
+                );
+            }
+            self.textarea.set_block(block);
+        }
+        f.render_widget(&self.textarea, area);
+    }
+
+    fn render_status_message(&self, f: &mut Frame, area: Rect) {
+        const SPINNER: [&str; 8] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧"];
+        let working = self.clipboard_job.as_ref().map(|(_, started)| {
+            let frame = (started.elapsed().as_millis() / 100) as usize % SPINNER.len();
+            format!("{} Working…", SPINNER[frame])
+        });
+        let visual = self.selection_anchor.map(|_| {
+            format!(
+                "-- VISUAL -- {} keys: r mark for review, x clear, y copy sources, Esc cancel",
+                self.selected_range_keys().len()
+            )
+        });
+        let msg = match (&self.pending_action, &self.status_message) {
+            _ if let Some(working) = &working => working.as_str(),
+            (Some(action @ (PendingAction::Replace | PendingAction::PasteLines)), _) => self
+                .pending_batch
+                .as_ref()
+                .map_or(action.prompt(), |(_, prompt)| prompt.as_str()),
+            (Some(action), _) => action.prompt(),
+            (None, Some((msg, _))) => msg.as_str(),
+            (None, None) if let Some(visual) = &visual => visual.as_str(),
+            (None, None) if self.source_changed => "Source changed on disk — press R to reload",
+            (None, None) if matches!(self.mode, AppMode::Editing) => {
+                "Ctrl+a/e start/end of line, Ctrl+k/u delete to end/start, Ctrl+w delete word, \
+                 Alt+Up/Down earlier translations"
+            }
+            (None, None) => return,
+        };
+        let footer = if self.color {
+            Paragraph::new(msg).style(Style::default().fg(self.theme.accent))
+        } else {
+            Paragraph::new(msg).style(Style::default())
+        };
+        f.render_widget(footer, area);
+    }
+
+    fn render_diff_overlay(&self, f: &mut Frame, area: Rect) {
+        let changes = self.translation_store.changed_items();
+        let describe = |text: &Option<String>| match text {
+            Some(t) => format!("{:?}", t),
+            None => "(untranslated)".to_string(),
+        };
+
+        let mut lines: Vec<Line> = Vec::new();
+        if changes.is_empty() {
+            lines.push(Line::from("No changes since the last save."));
+        }
+        for (key, old, new) in &changes {
+            lines.push(Line::from(Span::styled(
+                key.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            let (old_style, new_style) = if self.color {
+                (
+                    Style::default().fg(self.theme.removed),
+                    Style::default().fg(self.theme.added),
+                )
+            } else {
+                (Style::default(), Style::default())
+            };
+            lines.push(Line::from(vec![
+                Span::raw("  - "),
+                Span::styled(describe(old), old_style),
+            ]));
+            lines.push(Line::from(vec![
+                Span::raw("  + "),
+                Span::styled(describe(new), new_style),
+            ]));
+        }
+
+        let popup = centered_rect(80, 80, area);
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title(format!(
+                "Perubahan ({}) — j/k to scroll, Esc to close",
+                changes.len()
+            )))
+            .scroll((self.diff_scroll, 0));
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    // Read-only overview of the selected folder: leaf counts and the first few
+    // keys still waiting for a translation.
+    fn render_folder_summary(&self, f: &mut Frame, area: Rect) {
+        let Some(node) = self
+            .get_selected_path()
+            .and_then(|path| self.get_node(&path))
+        else {
+            return;
+        };
+        let (translated, total) = node.progress;
+        let untranslated = node.untranslated_leaves(FOLDER_SUMMARY_KEYS);
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Keys: ", bold),
+                Span::raw(total.to_string()),
+            ]),
+            Line::from(vec![
+                Span::styled("Translated: ", bold),
+                Span::raw(translated.to_string()),
+            ]),
+            Line::from(vec![
+                Span::styled("Untranslated: ", bold),
+                Span::raw((total - translated).to_string()),
+            ]),
+        ];
+        if !untranslated.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled("Still untranslated:", bold)));
+            let key_style = if self.color {
+                Style::default().fg(self.theme.untranslated)
+            } else {
+                Style::default()
+            };
+            for key in &untranslated {
+                lines.push(Line::from(Span::styled(format!("  {}", key), key_style)));
+            }
+            if total - translated > untranslated.len() {
+                lines.push(Line::from(format!(
+                    "  … and {} more",
+                    total - translated - untranslated.len()
+                )));
+            }
+        }
+
+        let popup = centered_rect(60, 50, area);
+        let paragraph = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("{} — Esc to close", node.full_path)),
+        );
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    fn render_rename_suggestions(&self, f: &mut Frame, area: Rect) {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let mut lines = Vec::new();
+        for (old_key, new_key, text) in &self.rename_suggestions {
+            lines.push(Line::from(vec![
+                Span::raw(old_key.clone()),
+                Span::raw(" → "),
+                Span::styled(new_key.clone(), bold),
             ]));
+            lines.push(Line::from(format!("    {:?}", text)));
         }
 
-        let source_paragraph = Paragraph::new(text_lines)
-            .block(Block::default().borders(Borders::ALL).title("Teks Sumber"));
-        f.render_widget(source_paragraph, area);
+        let popup = centered_rect(80, 60, area);
+        let paragraph =
+            Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!(
+                "Renamed keys? ({}) — y to carry the translations over, n to skip",
+                self.rename_suggestions.len()
+            )));
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+
+    // Pairs each new key with an orphaned key that was translated from the same
+    // source text, going by the hashes saved in `<out>.sources.json`, or whose
+    // text is still a copy of it, and offers to move the translation over.
+    fn suggest_renames(&mut self, report: &MergeReport) {
+        if self.readonly {
+            return;
+        }
+        let mut claimed: HashSet<&str> = HashSet::new();
+        for new_key in &report.new_keys {
+            let Some(item) = self.translation_store.all_items.get(new_key) else {
+                continue;
+            };
+            let source_hash = source_hashes::hash(&item.source_text);
+            let old_key = report.orphaned_keys.iter().find(|old| {
+                !claimed.contains(old.as_str())
+                    && (self.source_hashes.get(*old) == Some(&source_hash)
+                        || report.orphaned_texts.get(*old) == Some(&item.source_text))
+            });
+            if let Some(old_key) = old_key
+                && let Some(text) = report.orphaned_texts.get(old_key)
+            {
+                claimed.insert(old_key);
+                self.rename_suggestions
+                    .push((old_key.clone(), new_key.clone(), text.clone()));
+            }
+        }
+        if !self.rename_suggestions.is_empty() {
+            self.mode = AppMode::Renames;
+        }
+    }
+
+    fn resolve_rename_suggestions(&mut self, accept: bool) {
+        let suggestions = std::mem::take(&mut self.rename_suggestions);
+        self.mode = AppMode::Normal;
+        if !accept {
+            return;
+        }
+        let changes: ChangeBatch = suggestions
+            .into_iter()
+            .map(|(_, new_key, text)| (new_key, Some(text), false))
+            .collect();
+        let count = changes.len();
+        self.apply_changes(changes);
+        self.status_message = Some((
+            format!("Carried over {} translations (u to undo)", count),
+            Instant::now(),
+        ));
+    }
+
+    // Expands every ancestor of `path` and moves the selection onto it.
+    fn reveal_path(&mut self, path: &str) {
+        let segments: Vec<&str> = path.split('.').collect();
+        for len in 1..segments.len() {
+            if let Some(node) = self.get_node_mut(&segments[..len].join(".")) {
+                node.expanded = true;
+                if let Some(position) = node
+                    .children
+                    .iter()
+                    .position(|child| child.key_segment == segments[len])
+                {
+                    node.revealed = node.revealed.max(position + 1);
+                }
+            }
+        }
+        self.update_visible_nodes();
+        if let Some(index) = self.visible_nodes.iter().position(|(p, _)| p == path) {
+            self.selected_index = index;
+        }
+    }
+
+    // Selecting a "… N more" row lists the folder's next page of children in
+    // its place.
+    fn reveal_more_if_selected(&mut self) {
+        let Some(parent) = self
+            .visible_nodes
+            .get(self.selected_index)
+            .and_then(|(path, _)| path.strip_suffix(MORE_ROW_SUFFIX))
+            .map(str::to_string)
+        else {
+            return;
+        };
+        if let Some(node) = self.get_node_mut(&parent) {
+            node.revealed += FOLDER_PAGE_SIZE;
+        }
+        self.update_visible_nodes();
+    }
+
+    fn jump_to_parent(&mut self) {
+        if let Some(path) = self.get_selected_path()
+            && let Some((parent, _)) = path.rsplit_once('.')
+            && let Some(index) = self.visible_nodes.iter().position(|(p, _)| p == parent)
+        {
+            self.selected_index = index;
+        }
+    }
+
+    // Children directly follow their folder in `visible_nodes` when it's expanded.
+    fn jump_to_first_child(&mut self) {
+        if let Some((_, depth)) = self.visible_nodes.get(self.selected_index)
+            && let Some((_, next_depth)) = self.visible_nodes.get(self.selected_index + 1)
+            && *next_depth == depth + 1
+        {
+            self.selected_index += 1;
+        }
+    }
+
+    // Rows between the visual-mode anchor and the cursor, inclusive.
+    fn selected_range(&self) -> Option<std::ops::RangeInclusive<usize>> {
+        let anchor = self
+            .selection_anchor?
+            .min(self.visible_nodes.len().saturating_sub(1));
+        Some(anchor.min(self.selected_index)..=anchor.max(self.selected_index))
+    }
+
+    // Keys of the translatable rows in the visual-mode selection; folders are skipped.
+    fn selected_range_keys(&self) -> Vec<String> {
+        let Some(range) = self.selected_range() else {
+            return Vec::new();
+        };
+        self.visible_nodes[range]
+            .iter()
+            .filter(|(path, _)| self.translation_store.all_items.contains_key(path))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    // Handles the keys that act on a visual-mode selection; returns false for
+    // anything else, which then moves the cursor or runs as usual.
+    fn handle_selection_key(&mut self, code: KeyCode) -> bool {
+        if self.readonly && matches!(code, KeyCode::Char('r' | 'x')) {
+            self.report_readonly();
+            return true;
+        }
+        let keys = self.selected_range_keys();
+        let msg = match code {
+            KeyCode::Esc => None,
+            KeyCode::Char('r') => {
+                let changes: ChangeBatch = keys
+                    .iter()
+                    .map(|key| {
+                        let item = &self.translation_store.all_items[key];
+                        (key.clone(), item.target_text.clone(), true)
+                    })
+                    .collect();
+                let count = changes.len();
+                self.apply_changes(changes);
+                Some(format!("Marked {} keys for review (u to undo)", count))
+            }
+            KeyCode::Char('x') => {
+                let changes: ChangeBatch =
+                    keys.iter().map(|key| (key.clone(), None, false)).collect();
+                let count = changes.len();
+                self.apply_changes(changes);
+                Some(format!("Cleared {} translations (u to undo)", count))
+            }
+            KeyCode::Char('y') => {
+                let text = keys
+                    .iter()
+                    .map(|key| self.translation_store.all_items[key].source_text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.start_clipboard_job(ClipboardJob::Copy {
+                    text,
+                    done_message: format!("Copied {} source texts", keys.len()),
+                });
+                None
+            }
+            _ => return false,
+        };
+        self.selection_anchor = None;
+        if let Some(msg) = msg {
+            self.status_message = Some((msg, Instant::now()));
+        }
+        true
+    }
+
+    fn toggle_wrap_source(&mut self) {
+        self.session.wrap_source = !self.session.wrap_source;
+        let msg = if self.session.wrap_source {
+            "Wrapping long texts"
+        } else {
+            "Truncating long texts"
+        };
+        let msg = match self.session.save(&self.output_path) {
+            Ok(_) => msg.to_string(),
+            Err(e) => format!("{} (not persisted: {})", msg, e),
+        };
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    fn toggle_bookmark(&mut self) {
+        let Some(path) = self.get_selected_path() else {
+            return;
+        };
+        let bookmarks = &mut self.session.bookmarks;
+        let msg = if let Some(index) = bookmarks.iter().position(|p| *p == path) {
+            bookmarks.remove(index);
+            format!("Removed bookmark {}", path)
+        } else {
+            bookmarks.push(path.clone());
+            let key_order = &self.translation_store.key_order;
+            bookmarks.sort_by(|a, b| key_order.compare(a, b));
+            format!("Bookmarked {}", path)
+        };
+        let msg = match self.session.save(&self.output_path) {
+            Ok(_) => msg,
+            Err(e) => format!("{} (not persisted: {})", msg, e),
+        };
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    // Jumps to the bookmark after the selection in key order, wrapping around.
+    fn next_bookmark(&mut self) {
+        let current = self.get_selected_path().unwrap_or_default();
+        let key_order = &self.translation_store.key_order;
+        let existing: Vec<&String> = self
+            .session
+            .bookmarks
+            .iter()
+            .filter(|path| self.node_index.contains_key(*path))
+            .collect();
+        let next = existing
+            .iter()
+            .find(|path| key_order.compare(path, &current).is_gt())
+            .or(existing.first())
+            .map(|path| path.to_string());
+        match next {
+            Some(path) => self.reveal_path(&path),
+            None => {
+                self.status_message = Some(("No bookmarks.".to_string(), Instant::now()));
+            }
+        }
+    }
+
+    // Copies the selected source text and opens it on the `--mt-url` website.
+    fn open_machine_translation(&mut self) {
+        let Some(template) = self.mt_url.as_deref() else {
+            self.status_message = Some((
+                "Set --mt-url to open a translation website.".to_string(),
+                Instant::now(),
+            ));
+            return;
+        };
+        let Some(text) = self
+            .get_selected_path()
+            .and_then(|path| self.translation_store.all_items.get(&path))
+            .map(|item| item.source_text.clone())
+        else {
+            return;
+        };
+        let url = template
+            .replace("{text}", &opener::percent_encode(&text))
+            .replace(
+                "{target_lang}",
+                &opener::percent_encode(self.target_lang.as_deref().unwrap_or("")),
+            );
+        let done_message = match opener::open(&url) {
+            Ok(_) => "Copied the source and opened the translation website.".to_string(),
+            Err(e) => format!("Copied the source, but couldn't open the website: {}", e),
+        };
+        self.start_clipboard_job(ClipboardJob::Copy { text, done_message });
+    }
+
+    fn start_clipboard_job(&mut self, job: ClipboardJob) {
+        let clipboard = Arc::clone(&self.clipboard);
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            let outcome = match job {
+                ClipboardJob::Copy { text, done_message } => ClipboardOutcome::Copied {
+                    result: clipboard.copy(&text).map_err(|e| e.to_string()),
+                    done_message,
+                },
+                ClipboardJob::Paste { path } => ClipboardOutcome::Pasted {
+                    result: clipboard.paste().map_err(|e| e.to_string()),
+                    path,
+                },
+                ClipboardJob::PasteLines { path } => ClipboardOutcome::PastedLines {
+                    result: clipboard.paste().map_err(|e| e.to_string()),
+                    path,
+                },
+            };
+            let _ = sender.send(outcome);
+        });
+        self.clipboard_job = Some((receiver, Instant::now()));
+    }
+
+    fn poll_clipboard_job(&mut self) {
+        let Some((receiver, _)) = &self.clipboard_job else {
+            return;
+        };
+        let outcome = match receiver.try_recv() {
+            Ok(outcome) => outcome,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => {
+                self.clipboard_job = None;
+                return;
+            }
+        };
+        self.clipboard_job = None;
+        let msg = match outcome {
+            ClipboardOutcome::Copied {
+                result: Ok(()),
+                done_message,
+            } => done_message,
+            ClipboardOutcome::Copied { result: Err(e), .. } => {
+                format!("Failed to copy to clipboard: {}", e)
+            }
+            ClipboardOutcome::Pasted {
+                result: Ok(text),
+                path,
+            } => {
+                // Pasting onto a folder does nothing
+                if self.translation_store.all_items.contains_key(&path) {
+                    self.apply_changes(vec![(path, Some(text), false)]);
+                }
+                "Pasted from clipboard!".to_string()
+            }
+            ClipboardOutcome::PastedLines {
+                result: Ok(text),
+                path,
+            } => match self.prepare_paste_lines(&text, &path) {
+                Some(msg) => msg,
+                // The confirmation takes over the footer
+                None => return,
+            },
+            ClipboardOutcome::Pasted { result: Err(e), .. }
+            | ClipboardOutcome::PastedLines { result: Err(e), .. } => {
+                format!("Failed to paste from clipboard: {}", e)
+            }
+        };
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    fn open_finder(&mut self) {
+        self.finder_input = TextArea::default();
+        self.finder_input.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Cari Kunci (Enter to jump, Esc to cancel)"),
+        );
+        self.update_finder_results();
+        self.mode = AppMode::Finder;
+    }
+
+    fn update_finder_results(&mut self) {
+        let query = self.finder_input.lines().join("");
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, &String)> = self
+            .translation_store
+            .all_items
+            .keys()
+            .filter(|key| self.in_scope(key))
+            .filter_map(|key| matcher.fuzzy_match(key, &query).map(|score| (score, key)))
+            .collect();
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+        self.finder_results = scored.into_iter().map(|(_, key)| key.clone()).collect();
+        self.finder_selected = 0;
+    }
+
+    fn submit_finder(&mut self) {
+        self.mode = AppMode::Normal;
+        if let Some(path) = self.finder_results.get(self.finder_selected).cloned() {
+            self.reveal_path(&path);
+        }
+    }
+
+    fn render_finder(&self, f: &mut Frame, area: Rect) {
+        let popup = centered_rect(70, 70, area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(popup);
+
+        let items: Vec<ListItem> = self
+            .finder_results
+            .iter()
+            .map(|key| ListItem::new(key.as_str()))
+            .collect();
+        let highlight_style = if self.color {
+            Style::default()
+                .bg(self.theme.selection)
+                .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().add_modifier(Modifier::REVERSED)
+        };
+        let results = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} matches", self.finder_results.len())),
+            )
+            .highlight_style(highlight_style)
+            .highlight_symbol(">> ");
+        let mut list_state = ratatui::widgets::ListState::default();
+        list_state.select(Some(self.finder_selected));
+
+        f.render_widget(Clear, popup);
+        f.render_widget(&self.finder_input, chunks[0]);
+        f.render_stateful_widget(results, chunks[1], &mut list_state);
+    }
+
+    fn finder_next(&mut self) {
+        if self.finder_selected + 1 < self.finder_results.len() {
+            self.finder_selected += 1;
+        }
+    }
+
+    fn finder_previous(&mut self) {
+        self.finder_selected = self.finder_selected.saturating_sub(1);
+    }
+
+    fn open_prompt(&mut self, kind: PromptKind, initial: &str) {
+        self.prompt_kind = Some(kind);
+        self.prompt_input = TextArea::new(vec![initial.to_string()]);
+        self.prompt_input.move_cursor(tui_textarea::CursorMove::End);
+        self.prompt_input
+            .set_block(Block::default().borders(Borders::ALL).title(kind.title()));
+        self.mode = AppMode::Prompt;
+    }
+
+    fn close_prompt(&mut self) {
+        self.prompt_kind = None;
+        self.mode = AppMode::Normal;
+    }
+
+    fn submit_prompt(&mut self) {
+        let input = self.prompt_input.lines().join("");
+        let Some(kind) = self.prompt_kind else {
+            return;
+        };
+        self.close_prompt();
+        match kind {
+            PromptKind::SaveAs => self.save_as(input.trim()),
+            PromptKind::Search => self.set_search(Some(input)),
+            PromptKind::Replace => self.prepare_replace(&input),
+            PromptKind::Note => self.set_note(input.trim()),
+            PromptKind::Goto => self.goto_key(input.trim().trim_end_matches('.')),
+        }
+    }
+
+    // Untranslated leaves at or below the selected node, in tree order.
+    fn untranslated_under_selection(&self) -> Vec<String> {
+        self.get_selected_path()
+            .and_then(|path| self.get_node(&path))
+            .map(|node| {
+                node.untranslated_leaves(usize::MAX)
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // One source text per line for an external translator; line breaks inside
+    // a text are written as `\n` so the lines can be pasted back in order.
+    fn copy_untranslated_sources(&mut self) {
+        let keys = self.untranslated_under_selection();
+        if keys.is_empty() {
+            self.status_message = Some(("Nothing untranslated here".to_string(), Instant::now()));
+            return;
+        }
+        let text = keys
+            .iter()
+            .map(|key| {
+                self.translation_store.all_items[key]
+                    .source_text
+                    .replace('\n', "\\n")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.start_clipboard_job(ClipboardJob::Copy {
+            text,
+            done_message: format!("Copied {} untranslated source texts", keys.len()),
+        });
+    }
+
+    // Pairs the clipboard's lines with the untranslated leaves under `path` in
+    // order, the reverse of ctrl+u, and asks before applying them as one undo
+    // step. Empty lines leave their key untranslated. Returns a message instead
+    // when there's nothing to confirm.
+    fn prepare_paste_lines(&mut self, text: &str, path: &str) -> Option<String> {
+        let keys: Vec<String> = match self.get_node(path) {
+            Some(node) => node
+                .untranslated_leaves(usize::MAX)
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        };
+        if keys.is_empty() {
+            return Some("Nothing untranslated here".to_string());
+        }
+        let lines: Vec<String> = text.lines().map(|line| line.replace("\\n", "\n")).collect();
+        let pairs: Vec<(String, String)> = keys
+            .iter()
+            .cloned()
+            .zip(lines.iter().cloned())
+            .filter(|(_, line)| !line.is_empty())
+            .collect();
+        if pairs.is_empty() {
+            return Some("The clipboard has no lines to paste".to_string());
+        }
+
+        let preview: Vec<String> = pairs
+            .iter()
+            .take(3)
+            .map(|(key, line)| format!("{}: {:?}", key, line))
+            .collect();
+        let prompt = format!(
+            "Paste {} lines? {}{}{} (y/n)",
+            pairs.len(),
+            if lines.len() != keys.len() {
+                format!(
+                    "WARNING: {} lines for {} untranslated keys! ",
+                    lines.len(),
+                    keys.len()
+                )
+            } else {
+                String::new()
+            },
+            preview.join("; "),
+            if pairs.len() > preview.len() {
+                "; …"
+            } else {
+                ""
+            },
+        );
+        let batch = pairs
+            .into_iter()
+            .map(|(key, line)| (key, Some(line), false))
+            .collect();
+        self.pending_batch = Some((batch, prompt));
+        self.pending_action = Some(PendingAction::PasteLines);
+        None
+    }
+
+    fn goto_key(&mut self, path: &str) {
+        if path.is_empty() {
+            return;
+        }
+        let msg = if self.get_node(path).is_none() {
+            format!("No key {}", path)
+        } else if !self.in_scope(path) {
+            format!("{} is outside the current scope", path)
+        } else {
+            self.reveal_path(path);
+            if self.get_selected_path().as_deref() == Some(path) {
+                return;
+            }
+            format!("{} is hidden by the current filter or search", path)
+        };
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    // Completes the last segment of the goto prompt's path against the
+    // children of the folder before it. Several matches are completed as far
+    // as they agree and listed in the status bar.
+    fn complete_goto(&mut self) {
+        let input = self.prompt_input.lines().join("");
+        let (parent, partial) = match input.rsplit_once('.') {
+            Some((parent, partial)) => (parent, partial),
+            None => ("", input.as_str()),
+        };
+        let siblings = if parent.is_empty() {
+            &self.tree
+        } else {
+            match self.get_node(parent) {
+                Some(node) => &node.children,
+                None => return,
+            }
+        };
+        let matches: Vec<&TreeNode> = siblings
+            .iter()
+            .filter(|node| node.key_segment.starts_with(partial))
+            .collect();
+        let (completed, listing) = match matches.as_slice() {
+            [] => return,
+            [only] if only.is_leaf() => (only.full_path.clone(), None),
+            [only] => (format!("{}.", only.full_path), None),
+            [first, rest @ ..] => {
+                let mut common = first.key_segment.as_str();
+                for node in rest {
+                    let shared = common
+                        .char_indices()
+                        .zip(node.key_segment.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map_or(common.len().min(node.key_segment.len()), |((i, _), _)| i);
+                    common = &common[..shared];
+                }
+                let listed: Vec<&str> = matches
+                    .iter()
+                    .take(8)
+                    .map(|node| node.key_segment.as_str())
+                    .collect();
+                let completed = if parent.is_empty() {
+                    common.to_string()
+                } else {
+                    format!("{}.{}", parent, common)
+                };
+                (completed, Some(listed.join("  ")))
+            }
+        };
+        if let Some(listing) = listing {
+            self.status_message = Some((listing, Instant::now()));
+        }
+        self.prompt_input = TextArea::new(vec![completed]);
+        self.prompt_input.move_cursor(tui_textarea::CursorMove::End);
+        self.prompt_input.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(PromptKind::Goto.title()),
+        );
+    }
+
+    fn set_note(&mut self, note: &str) {
+        let Some(path) = self.get_selected_path() else {
+            return;
+        };
+        let msg = if note.is_empty() {
+            if self.notes.remove(&path).is_none() {
+                return;
+            }
+            format!("Removed the note on {}", path)
+        } else {
+            self.notes.insert(path.clone(), note.to_string());
+            format!("Saved the note on {}", path)
+        };
+        let msg = match notes::save(&self.output_path, &self.notes) {
+            Ok(_) => msg,
+            Err(e) => format!("{} (not persisted: {})", msg, e),
+        };
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    // Computes the substitution over every translation and asks for confirmation,
+    // previewing the first few changes.
+    fn prepare_replace(&mut self, input: &str) {
+        let substitution = match Substitution::parse(input) {
+            Ok(substitution) => substitution,
+            Err(e) => {
+                self.status_message = Some((e, Instant::now()));
+                return;
+            }
+        };
+        let mut changes: Vec<(String, String, String)> = self
+            .translation_store
+            .all_items
+            .values()
+            .filter_map(|item| {
+                let text = item.target_text.as_ref()?;
+                let replaced = substitution.apply(text)?;
+                (replaced != *text).then(|| (item.key.clone(), text.clone(), replaced))
+            })
+            .collect();
+        if changes.is_empty() {
+            self.status_message = Some(("No translations match.".to_string(), Instant::now()));
+            return;
+        }
+        changes.sort_by(|a, b| self.translation_store.key_order.compare(&a.0, &b.0));
+
+        let preview: Vec<String> = changes
+            .iter()
+            .take(3)
+            .map(|(key, old, new)| format!("{}: {:?} → {:?}", key, old, new))
+            .collect();
+        let all_identical = changes.len() > 1 && changes.iter().all(|c| c.2 == changes[0].2);
+        let prompt = format!(
+            "Replace in {} keys? {}{}{} (y/n)",
+            changes.len(),
+            if all_identical {
+                "WARNING: every result is identical! "
+            } else {
+                ""
+            },
+            preview.join("; "),
+            if changes.len() > preview.len() {
+                "; …"
+            } else {
+                ""
+            },
+        );
+        let batch = changes
+            .into_iter()
+            .map(|(key, _, new)| {
+                let needs_review = self.translation_store.all_items[&key].needs_review;
+                (key, Some(new), needs_review)
+            })
+            .collect();
+        self.pending_batch = Some((batch, prompt));
+        self.pending_action = Some(PendingAction::Replace);
+    }
+
+    fn save_as(&mut self, input: &str) {
+        if input.is_empty() {
+            self.status_message = Some(("No path given.".to_string(), Instant::now()));
+            return;
+        }
+        let new_path = PathBuf::from(input);
+        if let Some(parent) = new_path.parent()
+            && !parent.as_os_str().is_empty()
+            && !parent.is_dir()
+            && !self.translation_store.create_dirs
+        {
+            self.status_message = Some((
+                format!("Directory does not exist: {}", parent.display()),
+                Instant::now(),
+            ));
+            return;
+        }
+
+        let previous_path = std::mem::replace(&mut self.output_path, new_path);
+        match self.save_translations() {
+            Ok(_) if self.dry_run => {
+                self.status_message = Some((
+                    format!(
+                        "Dry run: would save to {}; nothing written",
+                        self.output_path.display()
+                    ),
+                    Instant::now(),
+                ));
+            }
+            Ok(_) => {
+                self.status_message = Some((
+                    format!("Saved to {}", self.output_path.display()),
+                    Instant::now(),
+                ));
+            }
+            Err(e) => {
+                self.output_path = previous_path;
+                self.status_message = Some((format!("Error saving file: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    fn render_prompt(&self, f: &mut Frame, area: Rect) {
+        let popup = centered_rect(60, 100, area);
+        let popup = Rect {
+            y: popup.y + popup.height.saturating_sub(3) / 2,
+            height: 3.min(popup.height),
+            ..popup
+        };
+        f.render_widget(Clear, popup);
+        f.render_widget(&self.prompt_input, popup);
+    }
+
+    fn report_readonly(&mut self) {
+        self.status_message = Some((
+            "Read-only: started with --readonly".to_string(),
+            Instant::now(),
+        ));
+    }
+
+    fn enter_editing_mode(&mut self) {
+        if self.readonly {
+            self.report_readonly();
+            return;
+        }
+        if self.get_selected_path().is_some() {
+            // Extract necessary data from the node while `self` is immutably borrowed
+            let (is_leaf, source_text, target_text) = {
+                if let Some(node) = self.get_selected_node() {
+                    if node.is_leaf() {
+                        let source = node
+                            .translation
+                            .as_ref()
+                            .map(|t| t.source_text.clone())
+                            .unwrap_or_default();
+                        let target = node
+                            .translation
+                            .as_ref()
+                            .and_then(|t| t.target_text.clone())
+                            .unwrap_or_default();
+                        (true, source, target)
+                    } else {
+                        (false, String::new(), String::new())
+                    }
+                } else {
+                    (false, String::new(), String::new())
+                }
+            };
+
+            // Now that the immutable borrow is dropped, we can mutably borrow `self`
+            if is_leaf {
+                // Untranslated ICU plurals start from the target language's categories
+                let target_text = if target_text.is_empty() {
+                    plural::skeleton(&source_text, &self.plural_categories).unwrap_or_default()
+                } else {
+                    target_text
+                };
+                self.mode = AppMode::Editing;
+                self.history_cursor = None;
+                self.textarea = TextArea::new(editor_lines(&target_text));
+                self.textarea.set_placeholder_text(source_text);
+                self.textarea.set_block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Edit Terjemahan (Ctrl+q to save, Tab to save & edit the next untranslated, Esc to cancel)")
+                        .style(self.editor_style()),
+                );
+            }
+        }
+    }
+
+    fn save_textarea_to_translation(&mut self) {
+        if let Some(path) = self.get_selected_path() {
+            let new_text = editor_text(self.textarea.lines());
+            let is_translated = !new_text.is_empty();
+
+            let text_to_save = if is_translated { Some(new_text) } else { None };
+
+            self.record_history(&path, text_to_save.as_deref());
+            self.apply_changes(vec![(path, text_to_save, false)]);
+        }
+    }
+
+    // Applies a batch of target-text changes as one undoable step.
+    fn apply_changes(&mut self, changes: ChangeBatch) {
+        let previous = self.write_changes(changes);
+        if !previous.is_empty() {
+            self.undo_stack.push(previous);
+        }
+    }
+
+    // Writes the changes to both the store and the tree, refreshing the tree
+    // status once at the end, and returns the values they replaced.
+    fn write_changes(&mut self, changes: ChangeBatch) -> ChangeBatch {
+        let mut previous: ChangeBatch = Vec::new();
+        for (path, text, needs_review) in changes {
+            let Some(item) = self.translation_store.all_items.get_mut(&path) else {
+                continue;
+            };
+            previous.push((path.clone(), item.target_text.clone(), item.needs_review));
+            item.target_text = text;
+            item.needs_review = needs_review;
+            item.refresh_checks(&self.plural_categories);
+            let item = item.clone();
+            if let Some(node) = self.get_node_mut(&path)
+                && node.translation.is_some()
+            {
+                node.translation = Some(item);
+            }
+        }
+        App::update_node_translation_status(&mut self.tree);
+        self.recount_remaining_words();
+        self.refresh_divergent_keys();
+        previous
+    }
+
+    fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(batch) => {
+                self.write_changes(batch);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn autosave(&mut self) {
+        if !self.translation_store.is_dirty() {
+            // Nothing to write; wait a full interval from now before checking again
+            self.last_save = Instant::now();
+            return;
+        }
+        let msg = match self.save_translations() {
+            Ok(_) => "Auto-saved.".to_string(),
+            Err(e) => {
+                // Don't retry on every frame after a failure
+                self.last_save = Instant::now();
+                format!("Auto-save failed: {}", e)
+            }
+        };
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    // Quits right away when nothing is unsaved; otherwise asks first. Any
+    // in-progress edit or overlay is abandoned.
+    fn request_quit(&mut self) -> bool {
+        if self.mode == AppMode::Editing {
+            self.exit_editing_mode_without_saving();
+        }
+        self.mode = AppMode::Normal;
+        if !self.translation_store.is_dirty() {
+            return true;
+        }
+        self.pending_action = Some(PendingAction::QuitUnsaved);
+        false
+    }
+
+    // Handles the answer to a y/n prompt; returns true when the app should quit.
+    fn resolve_pending_action(&mut self, action: PendingAction, key: KeyCode) -> bool {
+        match (action, key) {
+            (PendingAction::FillUntranslated, KeyCode::Char('y')) => {
+                let count = self.fill_untranslated_with_source();
+                self.status_message = Some((
+                    format!("Filled {} keys with source text (u to undo)", count),
+                    Instant::now(),
+                ));
+            }
+            (PendingAction::FixWhitespace, KeyCode::Char('y')) => {
+                let count = self.fix_edge_whitespace();
+                self.status_message = Some((
+                    format!("Fixed whitespace in {} keys (u to undo)", count),
+                    Instant::now(),
+                ));
+            }
+            (PendingAction::QuitUnsaved, KeyCode::Char('y')) => match self.save_translations() {
+                Ok(_) => return true,
+                Err(e) => {
+                    self.status_message =
+                        Some((format!("Error saving file: {}", e), Instant::now()));
+                }
+            },
+            (PendingAction::QuitUnsaved, KeyCode::Char('n')) => return true,
+            (PendingAction::Replace, KeyCode::Char('y')) => {
+                if let Some((batch, _)) = self.pending_batch.take() {
+                    let count = batch.len();
+                    self.apply_changes(batch);
+                    self.status_message = Some((
+                        format!("Replaced in {} keys (u to undo)", count),
+                        Instant::now(),
+                    ));
+                }
+            }
+            (PendingAction::PasteLines, KeyCode::Char('y')) => {
+                if let Some((batch, _)) = self.pending_batch.take() {
+                    let count = batch.len();
+                    self.apply_changes(batch);
+                    self.status_message = Some((
+                        format!("Pasted {} translations (u to undo)", count),
+                        Instant::now(),
+                    ));
+                }
+            }
+            _ => {
+                self.pending_batch = None;
+                self.status_message = Some(("Cancelled.".to_string(), Instant::now()));
+            }
+        }
+        false
+    }
+
+    fn fill_untranslated_with_source(&mut self) -> usize {
+        let changes: ChangeBatch = self
+            .translation_store
+            .all_items
+            .values()
+            .filter(|item| !item.is_translated())
+            .map(|item| (item.key.clone(), Some(item.source_text.clone()), true))
+            .collect();
+        let count = changes.len();
+        self.apply_changes(changes);
+        count
+    }
+
+    fn fix_edge_whitespace(&mut self) -> usize {
+        let changes: ChangeBatch = self
+            .translation_store
+            .all_items
+            .values()
+            .filter(|item| item.has_edge_whitespace_mismatch())
+            .map(|item| {
+                (
+                    item.key.clone(),
+                    item.with_source_edge_whitespace(),
+                    item.needs_review,
+                )
+            })
+            .collect();
+        let count = changes.len();
+        self.apply_changes(changes);
+        count
     }
 
-    fn render_editor(&self, f: &mut Frame, area: Rect) {
-        f.render_widget(&self.textarea, area);
+    // Keeps the translation `key` had before being changed to `new_text` in its
+    // per-key history, which is persisted with the session.
+    fn record_history(&mut self, key: &str, new_text: Option<&str>) {
+        let Some(previous) = self
+            .translation_store
+            .all_items
+            .get(key)
+            .and_then(|item| item.target_text.clone())
+            .filter(|previous| !previous.is_empty() && Some(previous.as_str()) != new_text)
+        else {
+            return;
+        };
+        if self.session.push_history(key, previous)
+            && let Err(e) = self.session.save(&self.output_path)
+        {
+            self.status_message = Some((
+                format!("Translation history not persisted: {}", e),
+                Instant::now(),
+            ));
+        }
     }
 
-    fn render_status_message(&self, f: &mut Frame, area: Rect) {
-        if let Some((msg, _)) = &self.status_message {
-            let footer = if self.color {
-                Paragraph::new(msg.as_str()).style(Style::default().fg(Color::LightYellow))
-            } else {
-                Paragraph::new(msg.as_str()).style(Style::default())
-            };
-            f.render_widget(footer, area);
+    // Steps the editor through the selected key's earlier translations;
+    // `older` moves back in time, and stepping past the newest entry restores
+    // the text the key has now.
+    fn cycle_history(&mut self, older: bool) {
+        let Some(path) = self.get_selected_path() else {
+            return;
+        };
+        let entries = self
+            .session
+            .history
+            .get(&path)
+            .map_or(&[][..], Vec::as_slice);
+        if entries.is_empty() {
+            self.status_message = Some((
+                "No earlier translations of this key.".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        let cursor = match (self.history_cursor, older) {
+            (None, true) => Some(entries.len() - 1),
+            (None, false) => None,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < entries.len() => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        let (text, msg) = match cursor {
+            Some(i) => (
+                entries[i].clone(),
+                format!(
+                    "Earlier translation {} of {}",
+                    entries.len() - i,
+                    entries.len()
+                ),
+            ),
+            None => (
+                self.translation_store
+                    .all_items
+                    .get(&path)
+                    .and_then(|item| item.target_text.clone())
+                    .unwrap_or_default(),
+                "Current translation".to_string(),
+            ),
+        };
+        self.history_cursor = cursor;
+        let block = self.textarea.block().cloned();
+        self.textarea = TextArea::new(editor_lines(&text));
+        if let Some(block) = block {
+            self.textarea.set_block(block);
         }
+        self.status_message = Some((msg, Instant::now()));
     }
 
-    fn enter_editing_mode(&mut self) {
-        if self.get_selected_path().is_some() {
-            // Extract necessary data from the node while `self` is immutably borrowed
-            let (is_leaf, source_text, target_text) = {
-                if let Some(node) = self.get_selected_node() {
-                    if node.is_leaf() {
-                        let source = node
-                            .translation
-                            .as_ref()
-                            .map(|t| t.source_text.clone())
-                            .unwrap_or_default();
-                        let target = node
-                            .translation
-                            .as_ref()
-                            .and_then(|t| t.target_text.clone())
-                            .unwrap_or_default();
-                        (true, source, target)
-                    } else {
-                        (false, String::new(), String::new())
-                    }
+    // The closest untranslated leaf after (or before) `current` in tree order
+    // within the scope, wrapping around; collapsed folders are looked into too.
+    fn untranslated_neighbour(&self, current: &str, forward: bool) -> Option<String> {
+        let leaves: Vec<&TreeNode> = self
+            .scope_roots()
+            .iter()
+            .flat_map(|root| root.leaves())
+            .collect();
+        let position = leaves.iter().position(|leaf| leaf.full_path == current)?;
+        let count = leaves.len();
+        (1..count)
+            .map(|step| {
+                if forward {
+                    (position + step) % count
                 } else {
-                    (false, String::new(), String::new())
+                    (position + count - step) % count
                 }
-            };
+            })
+            .map(|i| leaves[i])
+            .find(|leaf| !leaf.is_translated())
+            .map(|leaf| leaf.full_path.clone())
+    }
 
-            // Now that the immutable borrow is dropped, we can mutably borrow `self`
-            if is_leaf {
-                self.mode = AppMode::Editing;
-                self.textarea = TextArea::new(target_text.lines().map(String::from).collect());
-                self.textarea.set_placeholder_text(source_text);
-                self.textarea.set_block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .title("Edit Terjemahan (Ctrl+q to save, Esc to cancel)")
-                        .style(Style::default().fg(Color::LightYellow)),
-                );
-            }
+    // Saves the edit and reopens the editor on the next (or previous)
+    // untranslated key, for a quick pass over everything left to do.
+    fn save_and_edit_untranslated(&mut self, forward: bool) {
+        let Some(current) = self.get_selected_path() else {
+            return;
+        };
+        self.exit_editing_mode_and_save();
+        let Some(next) = self.untranslated_neighbour(&current, forward) else {
+            self.status_message = Some(("No untranslated keys left.".to_string(), Instant::now()));
+            return;
+        };
+        self.reveal_path(&next);
+        // A status filter or search can hide the key from the list
+        if self.get_selected_path().as_deref() == Some(next.as_str()) {
+            self.enter_editing_mode();
+        } else {
+            self.status_message = Some((
+                format!("{} is hidden by the current filter", next),
+                Instant::now(),
+            ));
         }
     }
 
-    fn save_textarea_to_translation(&mut self) {
-        if let Some(path) = self.get_selected_path() {
-            let new_text = self.textarea.lines().join("\n");
-            let is_translated = !new_text.is_empty();
-
-            let text_to_save = if is_translated { Some(new_text) } else { None };
-
-            if let Some(item) = self.translation_store.all_items.get_mut(&path) {
-                item.target_text = text_to_save.clone();
-            }
-            if let Some(node) = self.get_node_mut(&path) {
-                if let Some(trans_item) = &mut node.translation {
-                    trans_item.target_text = text_to_save;
-                }
-            }
-            App::update_node_translation_status(&mut self.tree);
+    fn insert_source_placeholder(&mut self, index: usize) {
+        let placeholder = self
+            .get_selected_path()
+            .and_then(|path| self.translation_store.all_items.get(&path))
+            .and_then(|item| {
+                extract_placeholders(&item.source_text)
+                    .into_iter()
+                    .nth(index)
+            });
+        if let Some(placeholder) = placeholder {
+            self.textarea.insert_str(placeholder);
         }
     }
 
@@ -522,6 +3087,7 @@ impl<'a> App<'a> {
     fn get_selected_path(&self) -> Option<String> {
         self.visible_nodes
             .get(self.selected_index)
+            .filter(|(path, _)| !path.ends_with(MORE_ROW_SUFFIX))
             .map(|(path, _)| path.clone())
     }
 
@@ -543,23 +3109,77 @@ fn ui(f: &mut Frame, app: &mut App) {
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(1), // Header for scrolloff and progress
+            Constraint::Length(1), // Breadcrumb of the selected key
             Constraint::Min(0),
             Constraint::Length(1),
         ]);
     let main_chunks = main_layout.split(f.area());
 
-    let header_text = format!(
-        "Translated: {}/{} ({:.2}%)",
-        translated_count, total_count, percentage
+    // Per-namespace mode shows the top-level folder holding the selected key
+    let namespace = app
+        .get_selected_path()
+        .filter(|_| app.namespace_progress)
+        .and_then(|path| {
+            let root = path.split('.').next()?.to_string();
+            app.get_node(&root)
+        });
+    let progress_text = match namespace {
+        Some(node) => {
+            let (done, all) = node.progress;
+            let node_percentage = if all > 0 {
+                (done as f64 / all as f64) * 100.0
+            } else {
+                0.0
+            };
+            format!(
+                "{}: {}/{} ({:.2}%)",
+                node.key_segment, done, all, node_percentage
+            )
+        }
+        None => format!(
+            "Translated: {}/{} ({:.2}%)",
+            translated_count, total_count, percentage
+        ),
+    };
+    let mut header_text = format!(
+        "{} | ~{} words left | Lang: {}",
+        progress_text,
+        app.remaining_words,
+        app.target_lang.as_deref().unwrap_or("unknown")
     );
+    if app.readonly {
+        header_text.push_str(" | READ-ONLY");
+    }
+    match app.status_filter {
+        StatusFilter::All => {}
+        StatusFilter::Untranslated => header_text.push_str(" | Filter: untranslated"),
+        StatusFilter::Translated => header_text.push_str(" | Filter: translated"),
+    }
+    if let Some(query) = &app.search_query {
+        header_text.push_str(&format!(" | Search: {}", query));
+    }
+    if let Some(scope) = &app.scope {
+        header_text.push_str(&format!(" | Scope: {}", scope));
+    }
     let mut header_paragraph = Paragraph::new(header_text);
-    header_paragraph = header_paragraph.style(Style::default().fg(Color::White).bg(Color::DarkGray));
+    if app.color {
+        header_paragraph = header_paragraph.style(
+            Style::default()
+                .fg(app.theme.header_fg)
+                .bg(app.theme.header_bg),
+        );
+    } else {
+        header_paragraph =
+            header_paragraph.style(Style::default().add_modifier(Modifier::REVERSED));
+    }
     f.render_widget(header_paragraph, main_chunks[0]);
 
+    app.render_breadcrumb(f, main_chunks[1]);
+
     let top_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(30), Constraint::Percentage(70)]);
-    let top_chunks = top_layout.split(main_chunks[1]);
+    let top_chunks = top_layout.split(main_chunks[2]);
 
     app.render_key_list(f, top_chunks[0]);
 
@@ -572,28 +3192,608 @@ fn ui(f: &mut Frame, app: &mut App) {
     // Panel Kanan Atas: Teks Sumber
     app.render_source_text(f, right_chunks[0]);
 
-    // Panel Kanan Bawah: Area Input
-    app.render_editor(f, right_chunks[1]);
+    // Panel Kanan Bawah: Area Input
+    app.render_editor(f, right_chunks[1]);
+
+    // Footer untuk status message
+    app.render_status_message(f, main_chunks[3]);
+
+    if app.mode == AppMode::Diff {
+        app.render_diff_overlay(f, f.area());
+    }
+    if app.mode == AppMode::Prompt {
+        app.render_prompt(f, f.area());
+    }
+    if app.mode == AppMode::Finder {
+        app.render_finder(f, f.area());
+    }
+    if app.mode == AppMode::Summary {
+        app.render_folder_summary(f, f.area());
+    }
+    if app.mode == AppMode::Renames {
+        app.render_rename_suggestions(f, f.area());
+    }
+}
+
+// Children listed per page when expanding a folder with many of them
+const FOLDER_PAGE_SIZE: usize = 100;
+
+// Ends the path of the "… N more" row after a partly listed folder's children;
+// no key can contain a NUL.
+const MORE_ROW_SUFFIX: &str = "\0more";
+
+// Untranslated keys listed in the folder summary before it says "and N more"
+const FOLDER_SUMMARY_KEYS: usize = 8;
+
+// Terminal cells reserved for a node's status marker, including the gap after it
+const STATUS_COLUMN_WIDTH: usize = 4;
+
+// Stand-ins for the status markers that aren't plain ASCII, under `--ascii`.
+fn ascii_marker(label: &'static str) -> &'static str {
+    match label {
+        "[✓]" => "[x]",
+        "[∅]" => "[0]",
+        "[⚠]" => "[%]",
+        "[≠]" => "[~]",
+        "[↻]" => "[^]",
+        other => other,
+    }
+}
+
+// Right-pads `text` with spaces to `width` terminal cells, measuring wide
+// (CJK, emoji) characters by their rendered width rather than by char count.
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(text.width());
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+// The editor's lines for `text`. Split on '\n' rather than `lines()` so a
+// trailing empty line survives the round-trip through the editor.
+fn editor_lines(text: &str) -> Vec<String> {
+    text.split('\n').map(String::from).collect()
+}
+
+// The text the editor's lines stand for; the inverse of `editor_lines`.
+fn editor_text(lines: &[String]) -> String {
+    lines.join("\n")
+}
+
+// Returns (character count, whitespace-delimited word count) for `text`.
+fn text_counts(text: &str) -> (usize, usize) {
+    (text.chars().count(), text.split_whitespace().count())
+}
+
+// Returns a rectangle of the given percentage size centered inside `area`.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+// Asks a yes/no question on the plain terminal, before the TUI starts.
+fn confirm_on_stdin(question: &str) -> io::Result<bool> {
+    use std::io::Write;
+    eprint!("{}", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+// A `.tmp` left by a save that was cut off before its rename. When it's complete
+// and newer than the output it's offered back, and removed if declined; otherwise
+// it's left alone, to be overwritten by the next save, and a warning is returned.
+fn recover_interrupted_save(
+    output_path: &PathBuf,
+    format: Option<FileFormat>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let tmp_path = TranslationStore::tmp_path_for(output_path);
+    let Ok(tmp_modified) = std::fs::metadata(&tmp_path).and_then(|m| m.modified()) else {
+        return Ok(None);
+    };
+    let newer = std::fs::metadata(output_path)
+        .and_then(|m| m.modified())
+        .is_ok_and(|modified| tmp_modified > modified)
+        || !output_path.exists();
+    if newer && TranslationStore::is_complete_save(&tmp_path, output_path, format) {
+        eprintln!(
+            "{} was left behind by a save that didn't finish.",
+            tmp_path.display()
+        );
+        if confirm_on_stdin(&format!(
+            "Recover it as {}? Otherwise it is deleted. [y/N] ",
+            output_path.display()
+        ))? {
+            std::fs::rename(&tmp_path, output_path)
+                .map_err(|e| format!("Failed to recover {}: {}", tmp_path.display(), e))?;
+        } else {
+            std::fs::remove_file(&tmp_path)
+                .map_err(|e| format!("Failed to remove {}: {}", tmp_path.display(), e))?;
+        }
+        Ok(None)
+    } else {
+        Ok(Some(format!(
+            "{} from an unfinished save {}; left as it is",
+            tmp_path.display(),
+            if newer {
+                "is incomplete".to_string()
+            } else {
+                format!("isn't newer than {}", output_path.display())
+            }
+        )))
+    }
+}
+
+// Writes `text` to a new file in the temp directory that only this user can
+// read. The file is created exclusively, so nothing already at a guessed name,
+// such as a symlink, is ever written through.
+fn write_private_temp_file(text: &str) -> io::Result<PathBuf> {
+    let mut attempt = 0;
+    loop {
+        let path =
+            std::env::temp_dir().join(format!("twoson-{}-{}.txt", std::process::id(), attempt));
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = match options.open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => {
+                attempt += 1;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if let Err(e) = file.write_all(text.as_bytes()) {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        return Ok(path);
+    }
+}
+
+// Suspends the TUI, opens the target text of `path` in `$VISUAL`/`$EDITOR` and
+// stores whatever the editor saved.
+fn edit_externally<B: Backend + std::io::Write>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    path: String,
+) -> io::Result<()> {
+    let original = app
+        .translation_store
+        .all_items
+        .get(&path)
+        .and_then(|item| item.target_text.clone())
+        .unwrap_or_default();
+    let tmp_path = match write_private_temp_file(&original) {
+        Ok(tmp_path) => tmp_path,
+        Err(e) => {
+            app.status_message =
+                Some((format!("Failed to write temp file: {}", e), Instant::now()));
+            return Ok(());
+        }
+    };
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Allow editors with arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&tmp_path)
+        .status();
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )?;
+    terminal.clear()?;
+
+    let edited = std::fs::read_to_string(&tmp_path);
+    let _ = std::fs::remove_file(&tmp_path);
+    let msg = match (status, edited) {
+        (Err(e), _) => format!("Failed to run {}: {}", program, e),
+        (Ok(status), _) if !status.success() => {
+            format!("{} exited with {}; translation unchanged", program, status)
+        }
+        (Ok(_), Err(e)) => format!("Failed to read temp file: {}", e),
+        (Ok(_), Ok(text)) => {
+            // Most editors end the file with a newline the translation didn't have
+            let text = text.strip_suffix('\n').unwrap_or(&text).to_string();
+            if text == original {
+                "No changes.".to_string()
+            } else {
+                let target = (!text.is_empty()).then_some(text);
+                app.record_history(&path, target.as_deref());
+                app.apply_changes(vec![(path, target, false)]);
+                "Translation updated from the external editor.".to_string()
+            }
+        }
+    };
+    app.status_message = Some((msg, Instant::now()));
+    Ok(())
+}
+
+// https://no-color.org: a non-empty NO_COLOR turns colors off by default, but an
+// explicit --color or config setting still wins.
+fn color_enabled(flag: Option<bool>, no_color: Option<&OsStr>) -> bool {
+    flag.unwrap_or_else(|| no_color.is_none_or(|value| value.is_empty()))
+}
+
+// `<out_prefix><source file name>` beside the source, refusing to take over an
+// existing file that isn't a translation of it unless `--force` is given.
+fn default_output_path(cli: &Cli) -> Result<PathBuf, Box<dyn Error>> {
+    let source_path = cli.source_path();
+    let file_name = source_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Source file path is not valid UTF-8 or has no filename.",
+            )
+        })?;
+    let new_file_name = format!("{}{}", cli.out_prefix, file_name);
+    let path = source_path.with_file_name(new_file_name);
+    if path.exists() && !cli.force && !looks_like_output(cli, &path) {
+        eprintln!(
+            "{} already exists and doesn't look like a translation of {}; \
+             pass --out to choose another file or --force to overwrite it.",
+            path.display(),
+            cli.source_path().display()
+        );
+        std::process::exit(1);
+    }
+    Ok(path)
+}
+
+// An existing file is a plausible output if it parses as translation JSON and
+// is either empty or shares at least one key with the source.
+fn looks_like_output(cli: &Cli, path: &PathBuf) -> bool {
+    match TranslationStore::load_from_files(
+        cli.source_path(),
+        Some(path),
+        cli.format,
+        cli.stream,
+        cli.ignore_key_case,
+    ) {
+        Ok((items, report)) => {
+            items.iter().any(|item| item.is_translated()) || report.orphaned_keys.is_empty()
+        }
+        Err(_) => false,
+    }
+}
+
+fn restore_terminal<B: Backend + std::io::Write>(
+    terminal: &mut Terminal<B>,
+) -> Result<(), Box<dyn Error>> {
+    disable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+// Shows a fatal error full-screen until a key is pressed, so it isn't lost the
+// moment the alternate screen goes away. Re-enters raw mode and the alternate
+// screen first in case the failure happened while they were left.
+fn show_error_panel<B: Backend + std::io::Write>(
+    terminal: &mut Terminal<B>,
+    err: &dyn Error,
+    app: &App,
+) -> Result<(), Box<dyn Error>> {
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+    let border_style = if app.color {
+        Style::default().fg(app.theme.removed)
+    } else {
+        Style::default()
+    };
+    let message = err.to_string();
+    let mut lines: Vec<Line> = message.lines().map(Line::from).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Press any key to exit.",
+        Style::default().add_modifier(Modifier::DIM),
+    )));
+    terminal.draw(|f| {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(" Error ");
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, f.area());
+    })?;
+    loop {
+        if let Event::Key(_) = event::read()? {
+            return Ok(());
+        }
+    }
+}
+
+// Turns keys that a `--fill-missing` save filled in back into untranslated ones.
+fn unfill_loaded(cli: &Cli, items: &mut [TranslationItem]) -> Result<(), Box<dyn Error>> {
+    if let Some(out) = &cli.out
+        && cli.edit.is_none()
+    {
+        source_hashes::unfill(items, cli.fill_missing, &source_hashes::load(out)?);
+    }
+    Ok(())
+}
+
+// ICU requires `other`; the rest depend on the target language.
+fn plural_categories(cli: &Cli, target_lang: Option<&str>) -> Vec<String> {
+    cli.plural_categories.clone().unwrap_or_else(|| {
+        target_lang
+            .and_then(locale::plural_categories)
+            .unwrap_or(&["other"])
+            .iter()
+            .map(|c| c.to_string())
+            .collect()
+    })
+}
+
+// Up to three keys, and how many more there are.
+fn list_keys(keys: &[String]) -> String {
+    let mut listed = keys[..keys.len().min(3)].join(", ");
+    if keys.len() > 3 {
+        listed.push_str(&format!(" and {} more", keys.len() - 3));
+    }
+    listed
+}
+
+// Ends a non-interactive mode so CI can gate on it: with `--fail-under`, exits
+// with status 2 when the translation is less complete than asked for, and with
+// status 1 when a translation fails the same validation `--strict` saves under.
+fn check_completeness(cli: &Cli, items: &[TranslationItem]) -> Result<(), Box<dyn Error>> {
+    let Some(threshold) = cli.fail_under else {
+        return Ok(());
+    };
+    let target_lang = cli.target_lang.clone().or_else(|| {
+        cli.edit
+            .as_ref()
+            .or(cli.out.as_ref())
+            .and_then(|path| locale::infer_locale(path))
+    });
+    let plural_categories = plural_categories(cli, target_lang.as_deref());
+    let failures: Vec<String> = items
+        .iter()
+        .filter(|item| item.fails_validation(&plural_categories))
+        .map(|item| item.key.clone())
+        .collect();
+    if !failures.is_empty() {
+        eprintln!(
+            "{} translation(s) failing validation: {}.",
+            failures.len(),
+            list_keys(&failures)
+        );
+    }
+    let translated = items.iter().filter(|item| item.is_translated()).count();
+    let percent = if items.is_empty() {
+        100.0
+    } else {
+        translated as f64 / items.len() as f64 * 100.0
+    };
+    if percent < threshold {
+        eprintln!(
+            "{:.2}% translated ({}/{}), below --fail-under {}%.",
+            percent,
+            translated,
+            items.len(),
+            threshold
+        );
+        std::process::exit(2);
+    }
+    if !failures.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error loading config: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = translation_data::set_placeholder_patterns(&config.placeholder_patterns) {
+        eprintln!("Invalid placeholder pattern in the config: {}", e);
+        std::process::exit(1);
+    }
+    let mut cli = Cli::parse().merge_config(config);
+    cli.color = Some(color_enabled(
+        cli.color,
+        std::env::var_os("NO_COLOR").as_deref(),
+    ));
+
+    // A downloaded source has nowhere to be saved back to or watched
+    if is_remote(cli.source_path()) {
+        let problem = if cli.edit.is_some() {
+            Some("--edit needs a local file")
+        } else if cli.out.is_none() {
+            Some("pass --out to choose where the translation is written")
+        } else if cli.watch {
+            Some("--watch only works with a local source file")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            eprintln!("{} is a URL: {}.", cli.source_path().display(), problem);
+            std::process::exit(1);
+        }
+    }
+
+    // Key events come from the terminal, so stdin can't also carry the source
+    if !cli.is_report_mode() && is_stdio(cli.source_path()) {
+        eprintln!(
+            "Reading the source from stdin (-) only works with --flag-identical, \
+             --flag-divergent, --merge-report or --report-md."
+        );
+        std::process::exit(1);
+    }
+
+    // Reports only read, so they have no output; they and `--readonly` leave a
+    // leftover temp file to the next session that can write
+    let mut recovery_note = None;
+    let output_path = if cli.is_report_mode() {
+        None
+    } else {
+        let output_path = match cli.edit.clone().or_else(|| cli.out.clone()) {
+            Some(path) => path,
+            None => default_output_path(&cli)?,
+        };
+        if !is_stdio(&output_path) && !cli.readonly {
+            recovery_note = recover_interrupted_save(&output_path, cli.format)?;
+        }
+        Some(output_path)
+    };
+
+    // Load translation items from files
+    let loaded = match &cli.edit {
+        Some(path) => TranslationStore::load_in_place(path, cli.stream)
+            .map(|items| (items, MergeReport::default())),
+        None => TranslationStore::load_from_files(
+            cli.source_path(),
+            cli.out.as_ref(),
+            cli.format,
+            cli.stream,
+            cli.ignore_key_case,
+        ),
+    };
+    let (mut items, merge_report) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) if e.is::<InvalidTargetError>() => {
+            eprintln!("Error loading target file: {}", e);
+            if !confirm_on_stdin("Continue with an empty target? Saving will overwrite it. [y/N] ")?
+            {
+                std::process::exit(1);
+            }
+            TranslationStore::load_from_files(
+                cli.source_path(),
+                None,
+                cli.format,
+                cli.stream,
+                cli.ignore_key_case,
+            )?
+        }
+        Err(e) => {
+            eprintln!("Error loading translation files: {}", e);
+            std::process::exit(1);
+        }
+    };
+    unfill_loaded(&cli, &mut items)?;
+
+    let key_order = KeyOrder::new(cli.sort_order(), &items);
+
+    // Non-interactive reports run without touching the terminal, on the items
+    // in key order; the app's store orders them itself
+    if cli.is_report_mode() {
+        items.sort_by(|a, b| key_order.compare(&a.key, &b.key));
+    }
+    if cli.flag_divergent {
+        let groups = divergent_groups(&items, &cli.allow_divergent);
+        for group in &groups {
+            println!("{:?}", group[0].source_text);
+            for item in group {
+                println!(
+                    "  {}\t{}",
+                    item.key,
+                    item.target_text.as_deref().unwrap_or("(untranslated)")
+                );
+            }
+        }
+        eprintln!(
+            "{} source text(s) with diverging translations.",
+            groups.len()
+        );
+        return check_completeness(&cli, &items);
+    }
+
+    if cli.flag_identical {
+        let identical: Vec<&TranslationItem> = items
+            .iter()
+            .filter(|item| item.is_identical_to_source(cli.normalize_whitespace))
+            .collect();
+        for item in &identical {
+            println!("{}", item.key);
+        }
+        eprintln!(
+            "{} translation(s) identical to the source.",
+            identical.len()
+        );
+        return check_completeness(&cli, &items);
+    }
+
+    if cli.merge_report {
+        for key in &merge_report.new_keys {
+            println!("new\t{}", key);
+        }
+        for key in &merge_report.orphaned_keys {
+            println!("orphaned\t{}", key);
+        }
+        for key in &merge_report.duplicate_keys {
+            println!("duplicate\t{}", key);
+        }
+        for key in &merge_report.case_collisions {
+            println!("case-collision\t{}", key);
+        }
+        eprintln!("{}.", merge_report.summary());
+        return check_completeness(&cli, &items);
+    }
 
-    // Footer untuk status message
-    app.render_status_message(f, main_chunks[2]);
-}
+    if let Some(report_path) = &cli.report_md {
+        let tree = App::build_tree(items.clone(), cli.tree_order);
+        let report = report::markdown_report(&tree, &items, cli.length_ratio);
+        if is_stdio(report_path) {
+            io::stdout().write_all(report.as_bytes())?;
+        } else {
+            std::fs::write(report_path, report)?;
+            eprintln!("Report written to {}", report_path.display());
+        }
+        return check_completeness(&cli, &items);
+    }
 
-fn restore_terminal<B: Backend + std::io::Write>(
-    terminal: &mut Terminal<B>,
-) -> Result<(), Box<dyn Error>> {
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    Ok(())
-}
+    // Buat app dan jalankan
+    let output_path = output_path.expect("only report modes run without an output");
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let cli = Cli::parse();
+    // Leave raw mode and the alternate screen before a panic message is printed
+    let default_panic_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        default_panic_hook(info);
+    }));
 
     // Setup terminal
     enable_raw_mode()?;
@@ -602,49 +3802,50 @@ fn main() -> Result<(), Box<dyn Error>> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Load translation items from files
-    let items = match TranslationStore::load_from_files(&cli.source_file, cli.out.as_ref()) {
-        Ok(items) => items,
-        Err(e) => {
-            restore_terminal(&mut terminal)?;
-            eprintln!("Error loading translation files: {}", e);
-            return Err(e);
-        }
-    };
-
-    // Buat app dan jalankan
-    let output_path = match cli.out.clone() {
-        Some(path) => path,
-        None => {
-            let source_path = &cli.source_file;
-            let file_name = source_path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "Source file path is not valid UTF-8 or has no filename.",
-                    )
-                })?;
-            let new_file_name = format!("id_{}", file_name);
-            source_path.with_file_name(new_file_name)
-        }
-    };
-    let mut app = match App::new(items, output_path, cli.color) {
+    let mut app = match App::new(items, key_order, output_path, &cli) {
         Ok(app) => app,
         Err(e) => {
             restore_terminal(&mut terminal)?;
             eprintln!("Error initializing app: {}", e);
-            return Err(e);
+            std::process::exit(1);
         }
     };
+    app.suggest_renames(&merge_report);
+    let mut startup_notes: Vec<String> = recovery_note.into_iter().collect();
+    if !merge_report.is_empty() {
+        startup_notes.push(merge_report.summary());
+    }
+    if app.clipboard.kind() == ClipboardKind::None && cli.clipboard.is_none() {
+        startup_notes.push(NO_CLIPBOARD_HINT.to_string());
+    }
+    if !startup_notes.is_empty() {
+        app.status_message = Some((startup_notes.join(" | "), Instant::now()));
+    }
     let res = run_app(&mut terminal, &mut app);
 
+    // Stop watching before tearing down the terminal
+    if let Some(watcher) = app.source_watcher.take() {
+        watcher.stop();
+    }
+
+    let shown = match &res {
+        Err(err) => show_error_panel(&mut terminal, err, &app).is_ok(),
+        Ok(()) => false,
+    };
+
     // Restore terminal
     restore_terminal(&mut terminal)?;
 
     if let Err(err) = res {
-        println!("Error in TUI: {:?}", err)
+        // Only printed when the panel couldn't be shown
+        if !shown {
+            eprintln!("Error in TUI: {}", err);
+        }
+        std::process::exit(1);
+    }
+
+    if let Some(output) = app.dry_run_output.take() {
+        io::stdout().write_all(&output)?;
     }
 
     Ok(())
@@ -657,138 +3858,421 @@ fn run_app<B: Backend + std::io::Write>(
     loop {
         terminal.draw(|f| ui(f, app))?;
 
-        if let Some((_, instant)) = app.status_message.as_ref() {
-            if instant.elapsed().as_secs() >= 2 {
-                app.status_message = None;
-            }
+        if let Some((_, instant)) = app.status_message.as_ref()
+            && instant.elapsed().as_secs() >= 2
+        {
+            app.status_message = None;
+        }
+
+        if let Some(watcher) = app.source_watcher.as_ref()
+            && watcher.has_changed()
+        {
+            app.source_changed = true;
+        }
+
+        if let Some(interval) = app.autosave_interval
+            && app.last_save.elapsed() >= interval
+        {
+            app.autosave();
+        }
+
+        app.poll_clipboard_job();
+
+        // Poll with a timeout so file-watch events are noticed without a key press;
+        // faster while a clipboard command runs, to animate the spinner
+        let timeout = if app.clipboard_job.is_some() {
+            100
+        } else {
+            250
+        };
+        if !event::poll(Duration::from_millis(timeout))? {
+            continue;
         }
 
-        if let Event::Key(key) = event::read()? {
+        let event = event::read()?;
+        if let Event::Resize(..) = event {
+            // Resize the buffer now and redraw straight away, which recomputes the
+            // list's scroll offset from the new height before any other input
+            terminal.autoresize()?;
+            continue;
+        }
+        if let Event::Mouse(mouse) = event
+            && app.mode == AppMode::Normal
+        {
+            handle_mouse_event(app, mouse);
+        }
+        if let Event::Key(key) = event {
+            // Raw mode swallows SIGINT, so Ctrl+C arrives as a key press
+            if key.code == KeyCode::Char('c')
+                && key.modifiers.contains(event::KeyModifiers::CONTROL)
+                && app.pending_action.is_none()
+            {
+                if app.request_quit() {
+                    return Ok(());
+                }
+                continue;
+            }
             match app.mode {
+                // The key list stays inert until the clipboard command returns
+                AppMode::Normal if app.clipboard_job.is_some() => {}
                 AppMode::Normal => {
                     if handle_normal_mode_events(app, key)? {
                         // If handler signals quit, break the loop
                         return Ok(());
                     }
+                    if let Some(path) = app.external_edit.take() {
+                        edit_externally(terminal, app, path)?;
+                    }
                 }
                 AppMode::Editing => {
                     handle_editing_mode_events(app, key)?;
                 }
+                AppMode::Diff => handle_diff_mode_events(app, key),
+                AppMode::Prompt => handle_prompt_mode_events(app, key),
+                AppMode::Finder => handle_finder_mode_events(app, key),
+                AppMode::Summary => {
+                    if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q')) {
+                        app.mode = AppMode::Normal;
+                    }
+                }
+                AppMode::Renames => match key.code {
+                    KeyCode::Char('y') | KeyCode::Enter => app.resolve_rename_suggestions(true),
+                    KeyCode::Char('n') | KeyCode::Esc => app.resolve_rename_suggestions(false),
+                    _ => {}
+                },
             }
         }
+        app.reveal_more_if_selected();
     }
 }
 
 // This is a new function
 fn handle_normal_mode_events(app: &mut App, key: event::KeyEvent) -> Result<bool, io::Error> {
-    match key.code {
-        KeyCode::Char('q') => return Ok(true), // Signal to quit
-        KeyCode::Char('s') => {
-            if app.save_translations().is_ok() {
-                app.status_message =
-                    Some(("File saved!".to_string(), Instant::now()));
+    if let Some(action) = app.pending_action.take() {
+        return Ok(app.resolve_pending_action(action, key.code));
+    }
+
+    // Digits build up a numeric prefix for the next command (e.g. `2z`)
+    if let KeyCode::Char(c) = key.code
+        && let Some(digit) = c.to_digit(10)
+        && !key.modifiers.contains(event::KeyModifiers::CONTROL)
+    {
+        let count = app.pending_count.unwrap_or(0);
+        app.pending_count = Some(count.saturating_mul(10).saturating_add(digit as usize));
+        return Ok(false);
+    }
+    let count = app.pending_count.take();
+
+    if app.selection_anchor.is_some() && app.handle_selection_key(key.code) {
+        return Ok(false);
+    }
+
+    let Some(action) = app.keymap.action_for(key) else {
+        return Ok(false);
+    };
+    if app.readonly && action.modifies() {
+        app.report_readonly();
+        return Ok(false);
+    }
+    match action {
+        Action::Quit => return Ok(true), // Signal to quit
+        Action::SaveAndQuit => {
+            if !app.translation_store.is_dirty() {
+                return Ok(true);
+            }
+            match app.save_translations() {
+                Ok(_) => return Ok(true),
+                Err(e) => {
+                    app.status_message =
+                        Some((format!("Error saving file: {}", e), Instant::now()));
+                }
+            }
+        }
+        Action::Save => {
+            let msg = match app.save_translations() {
+                Ok(_) if app.dry_run => {
+                    "Dry run: nothing written; the output is printed on exit.".to_string()
+                }
+                Ok(_) => "File saved!".to_string(),
+                Err(e) => format!("Error saving file: {}", e),
+            };
+            app.status_message = Some((msg, Instant::now()));
+        }
+        Action::FillUntranslated => {
+            app.pending_action = Some(PendingAction::FillUntranslated);
+        }
+        Action::FixWhitespace => {
+            app.pending_action = Some(PendingAction::FixWhitespace);
+        }
+        Action::ShowDiff => {
+            app.diff_scroll = 0;
+            app.mode = AppMode::Diff;
+        }
+        Action::Undo => {
+            let msg = if app.undo() {
+                "Undone."
             } else {
+                "Nothing to undo."
+            };
+            app.status_message = Some((msg.to_string(), Instant::now()));
+        }
+        Action::MarkEmpty => {
+            if let Some(path) = app.get_selected_path()
+                && app.translation_store.all_items.contains_key(&path)
+            {
+                app.apply_changes(vec![(path, Some(String::new()), false)]);
                 app.status_message =
-                    Some(("Error saving file!".to_string(), Instant::now()));
+                    Some(("Marked as intentionally empty.".to_string(), Instant::now()));
             }
         }
-        KeyCode::Char('y') => {
-            if let Some(path) = app.get_selected_path() {
-                if let Some(item) = app.translation_store.all_items.get(&path) {
-                    let text_to_copy = item.source_text.clone();
-                    match app.clipboard.copy(&text_to_copy) {
-                        Ok(_) => {
-                            app.status_message = Some((
-                                "Copied to clipboard!".to_string(),
-                                Instant::now(),
-                            ));
-                        }
-                        Err(e) => {
-                            app.status_message = Some((
-                                format!("Failed to copy to clipboard: {}", e),
-                                Instant::now(),
-                            ));
-                        }
-                    }
-                }
+        Action::Search => {
+            let current = app.search_query.clone().unwrap_or_default();
+            app.open_prompt(PromptKind::Search, &current);
+        }
+        Action::Replace => app.open_prompt(PromptKind::Replace, "s/"),
+        Action::UnifyDivergent => app.unify_divergent(),
+        Action::ToggleScope => app.toggle_scope(),
+        Action::CycleHeader => app.namespace_progress = !app.namespace_progress,
+        Action::ClearSearch => {
+            if app.search_query.is_some() {
+                app.set_search(None);
             }
         }
-        KeyCode::Char('p') => {
-            let mut pasted_text: Option<String> = None;
-            let status_msg = match app.clipboard.paste() {
-                Ok(text) => {
-                    pasted_text = Some(text);
-                    "Pasted from clipboard!".to_string()
-                }
-                Err(e) => {
-                    format!("Failed to paste from clipboard: {}", e)
-                }
+        Action::FilterUntranslated => app.toggle_status_filter(StatusFilter::Untranslated),
+        Action::FilterTranslated => app.toggle_status_filter(StatusFilter::Translated),
+        Action::ClearFilters => {
+            app.status_filter = StatusFilter::All;
+            app.set_search(None);
+        }
+        Action::SaveAs => {
+            let current = app.output_path.display().to_string();
+            app.open_prompt(PromptKind::SaveAs, &current);
+        }
+        Action::Reload => match app.reload_source() {
+            Ok(_) => {
+                app.status_message = Some(("Source reloaded!".to_string(), Instant::now()));
+            }
+            Err(e) => {
+                app.status_message =
+                    Some((format!("Failed to reload source: {}", e), Instant::now()));
+            }
+        },
+        Action::Copy => {
+            if let Some(path) = app.get_selected_path()
+                && let Some(item) = app.translation_store.all_items.get(&path)
+            {
+                let text = item.source_text.clone();
+                app.start_clipboard_job(ClipboardJob::Copy {
+                    text,
+                    done_message: "Copied to clipboard!".to_string(),
+                });
+            }
+        }
+        Action::OpenMachineTranslation => app.open_machine_translation(),
+        Action::ToggleWrap => app.toggle_wrap_source(),
+        Action::VisualSelect => {
+            app.selection_anchor = match app.selection_anchor {
+                Some(_) => None,
+                None => Some(app.selected_index),
             };
-
-            if let Some(text) = pasted_text {
-                if let Some(path) = app.get_selected_path() {
-                    if let Some(item) =
-                        app.translation_store.all_items.get_mut(&path)
-                    {
-                        item.target_text = Some(text.clone());
-                    }
-                    if let Some(node) = app.get_node_mut(&path) {
-                        if let Some(trans_item) = &mut node.translation {
-                            trans_item.target_text = Some(text);
-                        }
+        }
+        Action::ExternalEdit => {
+            if app.get_selected_node().is_some_and(|node| node.is_leaf()) {
+                app.external_edit = app.get_selected_path();
+            }
+        }
+        Action::JumpToParent => app.jump_to_parent(),
+        Action::JumpToFirstChild => app.jump_to_first_child(),
+        Action::ToggleBookmark => app.toggle_bookmark(),
+        Action::EditNote => {
+            if let Some(path) = app.get_selected_path() {
+                let current = app.notes.get(&path).cloned().unwrap_or_default();
+                app.open_prompt(PromptKind::Note, &current);
+            }
+        }
+        Action::NextBookmark => app.next_bookmark(),
+        Action::CopyKey => {
+            if let Some(path) = app.get_selected_path() {
+                app.start_clipboard_job(ClipboardJob::Copy {
+                    done_message: format!("Copied key {}", path),
+                    text: path,
+                });
+            }
+        }
+        Action::CopySnippet => {
+            if let Some(path) = app.get_selected_path() {
+                match app.translation_store.json_snippet(&path) {
+                    Some(text) => app.start_clipboard_job(ClipboardJob::Copy {
+                        done_message: format!("Copied {} as JSON", path),
+                        text,
+                    }),
+                    None => {
+                        app.status_message =
+                            Some((format!("Nothing translated under {}", path), Instant::now()))
                     }
-                    App::update_node_translation_status(&mut app.tree);
                 }
             }
-            app.status_message = Some((status_msg, Instant::now()));
-        }
-        KeyCode::Down | KeyCode::Char('j') => app.next(),
-        KeyCode::Up | KeyCode::Char('k') => app.previous(),
-        KeyCode::Char(' ') => app.toggle_expand(),
-        KeyCode::Right | KeyCode::Char('l') => {
-            if let Some(path_before_toggle) = app.get_selected_path() {
-                if let Some(node) = app.get_node_mut(&path_before_toggle) {
-                    if !node.is_leaf() && !node.expanded {
-                        node.expanded = true;
-                        app.update_visible_nodes();
-                        // Reselect the same node after update
-                        if let Some(new_index) = app.visible_nodes.iter().position(|(p, _)| p == &path_before_toggle) {
-                            app.selected_index = new_index;
-                        }
-                    }
+        }
+        Action::OpenFinder => app.open_finder(),
+        Action::GotoKey => app.open_prompt(PromptKind::Goto, ""),
+        Action::CopyUntranslated => app.copy_untranslated_sources(),
+        Action::PasteLines => {
+            if let Some(path) = app.get_selected_path() {
+                app.start_clipboard_job(ClipboardJob::PasteLines { path });
+            }
+        }
+        Action::Paste => {
+            if let Some(path) = app.get_selected_path() {
+                app.start_clipboard_job(ClipboardJob::Paste { path });
+            }
+        }
+        Action::Next => app.next_by(count.unwrap_or(1)),
+        Action::Previous => app.previous_by(count.unwrap_or(1)),
+        Action::ToggleExpand => app.toggle_expand(),
+        Action::ToggleSubtree => app.toggle_subtree(),
+        Action::ExpandToDepth => app.expand_to_depth(count.unwrap_or(0)),
+        Action::Expand => {
+            if let Some(path_before_toggle) = app.get_selected_path()
+                && let Some(node) = app.get_node_mut(&path_before_toggle)
+                && !node.is_leaf()
+                && !node.expanded
+            {
+                node.expanded = true;
+                app.update_visible_nodes();
+                // Reselect the same node after update
+                if let Some(new_index) = app
+                    .visible_nodes
+                    .iter()
+                    .position(|(p, _)| p == &path_before_toggle)
+                {
+                    app.selected_index = new_index;
                 }
             }
         }
-        KeyCode::Left | KeyCode::Char('h') => {
-            if let Some(path_before_toggle) = app.get_selected_path() {
-                if let Some(node) = app.get_node_mut(&path_before_toggle) {
-                    if !node.is_leaf() && node.expanded {
-                        node.expanded = false;
-                        app.update_visible_nodes();
-                        // Reselect the same node after update
-                        if let Some(new_index) = app.visible_nodes.iter().position(|(p, _)| p == &path_before_toggle) {
-                            app.selected_index = new_index;
-                        }
-                    }
+        Action::Collapse => {
+            if let Some(path_before_toggle) = app.get_selected_path()
+                && let Some(node) = app.get_node_mut(&path_before_toggle)
+                && !node.is_leaf()
+                && node.expanded
+            {
+                node.expanded = false;
+                app.update_visible_nodes();
+                // Reselect the same node after update
+                if let Some(new_index) = app
+                    .visible_nodes
+                    .iter()
+                    .position(|(p, _)| p == &path_before_toggle)
+                {
+                    app.selected_index = new_index;
                 }
             }
         }
-        KeyCode::Enter => {
+        Action::Edit => {
             if let Some(path) = app.get_selected_path() {
-                let is_leaf = app.get_node(&path).map_or(false, |n| n.is_leaf());
+                let is_leaf = app.get_node(&path).is_some_and(|n| n.is_leaf());
 
                 if is_leaf {
                     app.enter_editing_mode();
+                } else {
+                    app.mode = AppMode::Summary;
                 }
             }
         }
-        _ => {}
     }
     Ok(false) // Do not quit
 }
 
+fn handle_finder_mode_events(app: &mut App, key: event::KeyEvent) {
+    let ctrl = key.modifiers.contains(event::KeyModifiers::CONTROL);
+    match key.code {
+        // Nothing chosen: the previous selection is left untouched
+        KeyCode::Esc => app.mode = AppMode::Normal,
+        KeyCode::Enter => app.submit_finder(),
+        KeyCode::Down => app.finder_next(),
+        KeyCode::Char('n') if ctrl => app.finder_next(),
+        KeyCode::Up => app.finder_previous(),
+        KeyCode::Char('p') if ctrl => app.finder_previous(),
+        _ => {
+            if app.finder_input.input(key) {
+                app.update_finder_results();
+            }
+        }
+    }
+}
+
+fn handle_prompt_mode_events(app: &mut App, key: event::KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.close_prompt(),
+        KeyCode::Enter => app.submit_prompt(),
+        KeyCode::Tab if app.prompt_kind == Some(PromptKind::Goto) => app.complete_goto(),
+        _ => {
+            app.prompt_input.input(key);
+        }
+    }
+}
+
+fn handle_diff_mode_events(app: &mut App, key: event::KeyEvent) {
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('D') => app.mode = AppMode::Normal,
+        KeyCode::Down | KeyCode::Char('j') => app.diff_scroll = app.diff_scroll.saturating_add(1),
+        KeyCode::Up | KeyCode::Char('k') => app.diff_scroll = app.diff_scroll.saturating_sub(1),
+        KeyCode::PageDown => app.diff_scroll = app.diff_scroll.saturating_add(10),
+        KeyCode::PageUp => app.diff_scroll = app.diff_scroll.saturating_sub(10),
+        _ => {}
+    }
+}
+
+fn handle_mouse_event(app: &mut App, mouse: MouseEvent) {
+    match mouse.kind {
+        MouseEventKind::ScrollDown => app.next(),
+        MouseEventKind::ScrollUp => app.previous(),
+        MouseEventKind::Down(MouseButton::Left)
+            if app.minimap_area.contains((mouse.column, mouse.row).into()) =>
+        {
+            let cell = (mouse.row - app.minimap_area.y) as usize;
+            let chunks = app.minimap_chunks(app.minimap_area.height as usize);
+            if let Some(chunk) = chunks.get(cell) {
+                app.selected_index = chunk.start;
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            let area = app.key_list_area;
+            // Ignore clicks on the borders or outside the key list
+            if mouse.column <= area.x
+                || mouse.column >= area.right().saturating_sub(1)
+                || mouse.row <= area.y
+                || mouse.row >= area.bottom().saturating_sub(1)
+            {
+                return;
+            }
+            let index = app.list_offset + (mouse.row - area.y - 1) as usize;
+            if index >= app.visible_nodes.len() {
+                return;
+            }
+
+            let is_double_click = app
+                .last_click
+                .is_some_and(|(i, at)| i == index && at.elapsed() < Duration::from_millis(400));
+            app.selected_index = index;
+            app.last_click = Some((index, Instant::now()));
+
+            // A single click only selects; a double click acts like Enter
+            if is_double_click {
+                app.last_click = None;
+                if app.get_selected_node().is_some_and(|n| n.is_leaf()) {
+                    app.enter_editing_mode();
+                } else {
+                    app.toggle_expand();
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
 // This is also a new function
 fn handle_editing_mode_events(app: &mut App, key: event::KeyEvent) -> Result<(), io::Error> {
+    let ctrl = key.modifiers.contains(event::KeyModifiers::CONTROL);
     match key.code {
         KeyCode::Esc => {
             app.exit_editing_mode_without_saving();
@@ -796,9 +4280,421 @@ fn handle_editing_mode_events(app: &mut App, key: event::KeyEvent) -> Result<(),
         KeyCode::Char('q') if key.modifiers.contains(event::KeyModifiers::CONTROL) => {
             app.exit_editing_mode_and_save();
         }
+        // Tab/Shift+Tab save and move on to the next/previous untranslated key
+        KeyCode::Tab => app.save_and_edit_untranslated(true),
+        KeyCode::BackTab => app.save_and_edit_untranslated(false),
+        // Alt+Up/Alt+Down step through this key's earlier translations
+        KeyCode::Up if key.modifiers.contains(event::KeyModifiers::ALT) => {
+            app.cycle_history(true);
+        }
+        KeyCode::Down if key.modifiers.contains(event::KeyModifiers::ALT) => {
+            app.cycle_history(false);
+        }
+        // Readline-style line editing, whatever tui-textarea binds these to
+        KeyCode::Char('a') if ctrl => app.textarea.move_cursor(tui_textarea::CursorMove::Head),
+        KeyCode::Char('e') if ctrl => app.textarea.move_cursor(tui_textarea::CursorMove::End),
+        KeyCode::Char('k') if ctrl => {
+            app.textarea.delete_line_by_end();
+        }
+        KeyCode::Char('u') if ctrl => {
+            app.textarea.delete_line_by_head();
+        }
+        KeyCode::Char('w') if ctrl => {
+            app.textarea.delete_word();
+        }
+        // Alt+1..9 inserts the Nth placeholder from the source text at the cursor
+        KeyCode::Char(c @ '1'..='9') if key.modifiers.contains(event::KeyModifiers::ALT) => {
+            app.insert_source_placeholder(c as usize - '1' as usize);
+        }
         _ => {
             app.textarea.input(key);
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(key: &str, source: &str, target: Option<&str>) -> TranslationItem {
+        TranslationItem {
+            key: key.to_string(),
+            source_text: source.to_string(),
+            target_text: target.map(String::from),
+            needs_review: false,
+            position: 0,
+            context: None,
+            valid_placeholders: true,
+            valid_plurals: true,
+            valid_whitespace: true,
+        }
+    }
+
+    fn test_app(dir: &std::path::Path, items: Vec<TranslationItem>) -> App<'static> {
+        let source_path = dir.join("en.json");
+        let cli = Cli::parse_from([
+            "twoson",
+            "--source-file",
+            source_path.to_str().unwrap(),
+            "--clipboard",
+            "none",
+        ]);
+        let key_order = KeyOrder::new(cli.sort_order(), &items);
+        App::new(items, key_order, dir.join("id.json"), &cli).unwrap()
+    }
+
+    fn edit_in_place(path: &std::path::Path) -> App<'static> {
+        let cli = Cli::parse_from([
+            "twoson",
+            "--edit",
+            path.to_str().unwrap(),
+            "--clipboard",
+            "none",
+        ]);
+        let items = TranslationStore::load_in_place(&path.to_path_buf(), false).unwrap();
+        let key_order = KeyOrder::new(cli.sort_order(), &items);
+        App::new(items, key_order, path.to_path_buf(), &cli).unwrap()
+    }
+
+    fn status_label(app: &App, path: &str) -> &'static str {
+        app.node_status_label(app.get_node(path).unwrap()).0
+    }
+
+    #[test]
+    fn missing_and_intentionally_empty_translations_differ() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = test_app(
+            dir.path(),
+            vec![
+                item("menu.missing", "Open", None),
+                item("menu.empty", "Suffix", Some("")),
+                item("menu.done", "Close", Some("Tutup")),
+                item("units.empty", "Suffix", Some("")),
+                item("units.done", "Close", Some("Tutup")),
+            ],
+        );
+        assert_eq!(status_label(&app, "menu.missing"), "[ ]");
+        assert_eq!(status_label(&app, "menu.empty"), "[∅]");
+        assert_eq!(status_label(&app, "menu.done"), "[✓]");
+        // The empty one counts as done, the missing one doesn't
+        assert_eq!(status_label(&app, "menu"), "[+]");
+        assert_eq!(status_label(&app, "units"), "[✓]");
+    }
+
+    #[test]
+    fn editor_footer_lists_the_line_editing_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(dir.path(), vec![item("title", "Title", None)]);
+        app.enter_editing_mode();
+        let footer = screen_rows(&mut app).pop().unwrap();
+        assert!(
+            footer.starts_with("Ctrl+a/e start/end of line"),
+            "{}",
+            footer
+        );
+    }
+
+    fn screen_rows(app: &mut App) -> Vec<String> {
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(160, 20)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unsaved_and_bookmark_markers_differ() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(dir.path(), vec![item("menu.file", "File", None)]);
+        app.expand_to_depth(2);
+        app.apply_changes(vec![(
+            "menu.file".to_string(),
+            Some("Berkas".to_string()),
+            false,
+        )]);
+        app.session.bookmarks.push("menu.file".to_string());
+        assert!(
+            screen_rows(&mut app)
+                .iter()
+                .any(|row| row.contains("file ● ★"))
+        );
+
+        app.ascii = true;
+        assert!(
+            screen_rows(&mut app)
+                .iter()
+                .any(|row| row.contains("file + *"))
+        );
+    }
+
+    #[test]
+    fn folders_toggle_on_double_click_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(dir.path(), vec![item("menu.file", "File", None)]);
+        app.key_list_area = Rect::new(0, 0, 40, 10);
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 5,
+            row: 1,
+            modifiers: event::KeyModifiers::NONE,
+        };
+
+        handle_mouse_event(&mut app, click);
+        assert_eq!(app.get_selected_path().as_deref(), Some("menu"));
+        assert!(!app.get_node("menu").unwrap().expanded);
+
+        handle_mouse_event(&mut app, click);
+        assert!(app.get_node("menu").unwrap().expanded);
+    }
+
+    #[test]
+    fn checks_are_refreshed_when_a_translation_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = test_app(
+            dir.path(),
+            vec![item("welcome", "Hi {name}", Some("Halo {name}"))],
+        );
+        assert_eq!(status_label(&app, "welcome"), "[✓]");
+
+        app.apply_changes(vec![(
+            "welcome".to_string(),
+            Some("Halo".to_string()),
+            false,
+        )]);
+        assert!(!app.translation_store.all_items["welcome"].valid_placeholders);
+        assert_eq!(status_label(&app, "welcome"), "[⚠]");
+        assert_eq!(app.validation_failures(), ["welcome"]);
+
+        app.apply_changes(vec![(
+            "welcome".to_string(),
+            Some("Halo {name} ".to_string()),
+            false,
+        )]);
+        assert_eq!(status_label(&app, "welcome"), "[_]");
+
+        assert!(app.undo());
+        assert_eq!(status_label(&app, "welcome"), "[⚠]");
+    }
+
+    #[test]
+    fn editor_round_trip_keeps_trailing_newline() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_path = dir.path().join("en.json");
+        let output_path = dir.path().join("id.json");
+        std::fs::write(&source_path, r#"{"greeting": "Hello\nworld\n"}"#).unwrap();
+
+        let text = "Halo\ndunia\n";
+        let edited = editor_text(&editor_lines(text));
+        assert_eq!(edited, text);
+
+        let (items, _) =
+            TranslationStore::load_from_files(&source_path, None, None, false, false).unwrap();
+        let mut store = TranslationStore::new(items, KeyOrder::default());
+        store.all_items.get_mut("greeting").unwrap().target_text = Some(edited);
+        store.save_translations(&output_path).unwrap();
+
+        let (items, _) =
+            TranslationStore::load_from_files(&source_path, Some(&output_path), None, false, false)
+                .unwrap();
+        assert_eq!(items[0].target_text.as_deref(), Some(text));
+    }
+
+    #[test]
+    fn renamed_keys_are_matched_by_saved_source_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut hashes = SourceHashes::new();
+        hashes.insert("menu.quit".to_string(), source_hashes::hash("Quit"));
+        source_hashes::save(&dir.path().join("id.json"), &hashes).unwrap();
+        let mut app = test_app(
+            dir.path(),
+            vec![
+                item("menu.exit", "Quit", None),
+                item("menu.help", "Help", None),
+            ],
+        );
+        let report = MergeReport {
+            new_keys: vec!["menu.exit".to_string(), "menu.help".to_string()],
+            orphaned_keys: vec!["menu.about".to_string(), "menu.quit".to_string()],
+            orphaned_texts: HashMap::from([
+                ("menu.about".to_string(), "Help".to_string()),
+                ("menu.quit".to_string(), "Keluar".to_string()),
+            ]),
+            ..MergeReport::default()
+        };
+
+        app.suggest_renames(&report);
+        assert_eq!(
+            app.rename_suggestions,
+            [
+                (
+                    "menu.quit".to_string(),
+                    "menu.exit".to_string(),
+                    "Keluar".to_string()
+                ),
+                // Never translated, but still a copy of the source
+                (
+                    "menu.about".to_string(),
+                    "menu.help".to_string(),
+                    "Help".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn external_edit_temp_files_are_new_and_private() {
+        let first = write_private_temp_file("Halo\n").unwrap();
+        let second = write_private_temp_file("Dunia").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "Halo\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&first).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        std::fs::remove_file(first).unwrap();
+        std::fs::remove_file(second).unwrap();
+    }
+
+    #[test]
+    fn edits_in_place_are_not_stale_after_a_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        std::fs::write(&path, r#"{"menu": {"file": "Berkas", "quit": "Keluar"}}"#).unwrap();
+
+        let mut app = edit_in_place(&path);
+        app.apply_changes(vec![(
+            "menu.file".to_string(),
+            Some("File".to_string()),
+            false,
+        )]);
+        app.save_translations().unwrap();
+        assert!(!dir.path().join("id.json.sources.json").exists());
+
+        let app = edit_in_place(&path);
+        for item in app.translation_store.sorted_items() {
+            assert!(!app.is_stale(item), "{} is stale", item.key);
+        }
+    }
+
+    #[test]
+    fn edit_in_place_loads_each_text_as_its_own_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("id.json");
+        std::fs::write(
+            &path,
+            r#"{"menu": {"file": "Berkas", "open": "Buka", "quit": "Buka"}}"#,
+        )
+        .unwrap();
+
+        let items = TranslationStore::load_in_place(&path, false).unwrap();
+        assert_eq!(items.len(), 3);
+        for item in &items {
+            assert_eq!(item.target_text.as_ref(), Some(&item.source_text));
+        }
+
+        let mut app = edit_in_place(&path);
+        assert_eq!(status_label(&app, "menu.file"), "[✓]");
+        app.apply_changes(vec![(
+            "menu.quit".to_string(),
+            Some("Keluar".to_string()),
+            false,
+        )]);
+        assert!(app.divergent_keys.is_empty());
+        assert_eq!(status_label(&app, "menu.open"), "[✓]");
+        assert_eq!(status_label(&app, "menu.quit"), "[✓]");
+    }
+
+    #[test]
+    fn filled_keys_load_back_untranslated() {
+        for (mode, deliberate, filled) in [
+            ("source", "OK", Some("Banana")),
+            ("empty", "", Some("")),
+            ("skip", "OK", None),
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let source_path = dir.path().join("en.json");
+            let output_path = dir.path().join("id.json");
+            std::fs::write(
+                &source_path,
+                r#"{"fruit": {"apple": "Apple", "banana": "Banana"}, "ok": "OK"}"#,
+            )
+            .unwrap();
+            let cli = Cli::parse_from([
+                "twoson",
+                "--source-file",
+                source_path.to_str().unwrap(),
+                "--out",
+                output_path.to_str().unwrap(),
+                "--fill-missing",
+                mode,
+                "--clipboard",
+                "none",
+            ]);
+            let load = || {
+                let (mut items, _) = TranslationStore::load_from_files(
+                    &source_path,
+                    Some(&output_path),
+                    None,
+                    false,
+                    false,
+                )
+                .unwrap();
+                unfill_loaded(&cli, &mut items).unwrap();
+                items
+            };
+
+            let items = load();
+            let key_order = KeyOrder::new(cli.sort_order(), &items);
+            let mut app = App::new(items, key_order, output_path.clone(), &cli).unwrap();
+            app.apply_changes(vec![
+                ("fruit.apple".to_string(), Some("Apel".to_string()), false),
+                ("ok".to_string(), Some(deliberate.to_string()), false),
+            ]);
+            app.save_translations().unwrap();
+            let saved: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&output_path).unwrap()).unwrap();
+            assert_eq!(saved["fruit"]["banana"].as_str(), filled, "{}", mode);
+
+            let items = load();
+            let target = |key: &str| {
+                let item = items.iter().find(|item| item.key == key).unwrap();
+                item.target_text.clone()
+            };
+            assert_eq!(target("fruit.apple").as_deref(), Some("Apel"), "{}", mode);
+            assert_eq!(target("fruit.banana"), None, "{}", mode);
+            assert_eq!(target("ok").as_deref(), Some(deliberate), "{}", mode);
+        }
+    }
+
+    #[test]
+    fn no_color_only_sets_the_default() {
+        let set = Some(OsStr::new("1"));
+        assert!(color_enabled(None, None));
+        assert!(!color_enabled(None, set));
+        // An empty NO_COLOR counts as unset
+        assert!(color_enabled(None, Some(OsStr::new(""))));
+        assert!(color_enabled(Some(true), set));
+        assert!(!color_enabled(Some(false), None));
+    }
+
+    #[test]
+    fn pad_to_width_measures_wide_characters() {
+        let marker = pad_to_width("[✓]", STATUS_COLUMN_WIDTH);
+        assert_eq!(marker.width(), STATUS_COLUMN_WIDTH);
+
+        let row = format!("{}{}", marker, pad_to_width("挨拶", 10));
+        assert_eq!(row.width(), STATUS_COLUMN_WIDTH + 10);
+        assert_eq!(pad_to_width("挨拶", 10), "挨拶      ");
+
+        // Already wider than asked for: left as is
+        assert_eq!(pad_to_width("🎉🎉🎉", 4), "🎉🎉🎉");
+    }
+}
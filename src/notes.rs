@@ -0,0 +1,44 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::translation_data::is_stdio;
+
+// Translator notes by dotted key, kept beside the output in `<out>.notes.json`
+// so they never end up in the translation file itself.
+pub type Notes = BTreeMap<String, String>;
+
+fn notes_path(output_path: &Path) -> Option<PathBuf> {
+    if is_stdio(output_path) {
+        return None;
+    }
+    let mut file_name = output_path.file_name()?.to_os_string();
+    file_name.push(".notes.json");
+    Some(output_path.with_file_name(file_name))
+}
+
+// No notes file yet just means no notes.
+pub fn load(output_path: &Path) -> Result<Notes, Box<dyn Error>> {
+    let Some(path) = notes_path(output_path).filter(|path| path.exists()) else {
+        return Ok(Notes::new());
+    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+// Removes the file once the last note is deleted.
+pub fn save(output_path: &Path, notes: &Notes) -> Result<(), Box<dyn Error>> {
+    let path = notes_path(output_path).ok_or("Notes can't be kept when writing to stdout.")?;
+    if notes.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+        return Ok(());
+    }
+    let contents = serde_json::to_string_pretty(notes)?;
+    std::fs::write(&path, contents + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::ops::Range;
+
+// An ICU MessageFormat argument with branches, such as
+// `{count, plural, one {# item} other {# items}}`.
+#[derive(Debug)]
+pub struct PluralBlock {
+    pub variable: String,
+    pub kind: String,
+    pub categories: Vec<String>,
+    pub bodies: Vec<String>,
+    pub span: Range<usize>,
+}
+
+const BRANCHING_KINDS: [&str; 3] = ["plural", "select", "selectordinal"];
+
+// Finds the outermost plural/select blocks in `text`; nested blocks stay inside
+// their parent's bodies.
+pub fn parse_blocks(text: &str) -> Vec<PluralBlock> {
+    let mut blocks = Vec::new();
+    let mut position = 0;
+    while let Some(offset) = text[position..].find('{') {
+        let start = position + offset;
+        let Some(end) = matching_brace(text, start) else {
+            break;
+        };
+        match parse_block(&text[start + 1..end]) {
+            Some(mut block) => {
+                block.span = start..end + 1;
+                blocks.push(block);
+                position = end + 1;
+            }
+            None => position = start + 1,
+        }
+    }
+    blocks
+}
+
+// Replaces every block with its `{variable}` followed by the text of its
+// branches, so placeholder extraction sees the argument and whatever the
+// branches use, but not the `{# items}` bodies themselves.
+pub fn flatten_blocks(text: &str) -> String {
+    let mut flattened = String::with_capacity(text.len());
+    let mut position = 0;
+    for block in parse_blocks(text) {
+        flattened.push_str(&text[position..block.span.start]);
+        flattened.push_str(&format!("{{{}}}", block.variable));
+        for body in &block.bodies {
+            flattened.push(' ');
+            flattened.push_str(&flatten_blocks(body));
+        }
+        position = block.span.end;
+    }
+    flattened.push_str(&text[position..]);
+    flattened
+}
+
+// Lists the `required` categories missing from any plural block in `text`,
+// including nested ones.
+pub fn missing_categories(text: &str, required: &[String]) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    for block in parse_blocks(text) {
+        if block.kind == "plural" {
+            for category in required {
+                if !block.categories.contains(category) && !missing.contains(category) {
+                    missing.push(category.clone());
+                }
+            }
+        }
+        for body in &block.bodies {
+            for category in missing_categories(body, required) {
+                if !missing.contains(&category) {
+                    missing.push(category);
+                }
+            }
+        }
+    }
+    missing
+}
+
+// The branching blocks of `text` with empty bodies, to be filled in for the
+// target language: plural blocks get `categories` plus any explicit `=N`
+// selectors of the source, select and selectordinal blocks keep the source's.
+// Text around the blocks is left out. `None` if `text` has no blocks.
+pub fn skeleton(text: &str, categories: &[String]) -> Option<String> {
+    let blocks = parse_blocks(text);
+    if blocks.is_empty() {
+        return None;
+    }
+    let skeletons: Vec<String> = blocks
+        .iter()
+        .map(|block| {
+            let selectors: Vec<&String> = if block.kind == "plural" {
+                block
+                    .categories
+                    .iter()
+                    .filter(|c| c.starts_with('='))
+                    .chain(categories)
+                    .collect()
+            } else {
+                block.categories.iter().collect()
+            };
+            let branches: Vec<String> = selectors
+                .iter()
+                .map(|selector| {
+                    // A nested block goes into every branch of its parent
+                    let nested = block
+                        .categories
+                        .iter()
+                        .position(|c| c == *selector)
+                        .or((block.kind == "plural").then_some(block.categories.len() - 1))
+                        .and_then(|i| skeleton(&block.bodies[i], categories))
+                        .unwrap_or_default();
+                    format!("{} {{{}}}", selector, nested)
+                })
+                .collect();
+            format!(
+                "{{{}, {}, {}}}",
+                block.variable,
+                block.kind,
+                branches.join(" ")
+            )
+        })
+        .collect();
+    Some(skeletons.join(" "))
+}
+
+fn parse_block(inner: &str) -> Option<PluralBlock> {
+    let mut parts = inner.splitn(3, ',');
+    let variable = parts.next()?.trim();
+    let kind = parts.next()?.trim();
+    let mut rest = parts.next()?;
+    if variable.is_empty()
+        || !variable.chars().all(|c| c.is_alphanumeric() || c == '_')
+        || !BRANCHING_KINDS.contains(&kind)
+    {
+        return None;
+    }
+
+    let mut categories = Vec::new();
+    let mut bodies = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        let open = rest.find('{')?;
+        let selector = rest[..open].trim();
+        let close = matching_brace(rest, open)?;
+        // `offset:1` may precede the first selector
+        let selector = match selector.split_once(char::is_whitespace) {
+            Some((first, second)) if first.starts_with("offset:") => second.trim(),
+            _ => selector,
+        };
+        if selector.is_empty() || selector.contains(char::is_whitespace) {
+            return None;
+        }
+        categories.push(selector.to_string());
+        bodies.push(rest[open + 1..close].to_string());
+        rest = &rest[close + 1..];
+    }
+    if categories.is_empty() {
+        return None;
+    }
+
+    Some(PluralBlock {
+        variable: variable.to_string(),
+        kind: kind.to_string(),
+        categories,
+        bodies,
+        span: 0..0,
+    })
+}
+
+// Byte index of the `}` closing the `{` at `open`.
+fn matching_brace(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
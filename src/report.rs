@@ -0,0 +1,101 @@
+use crate::TreeNode;
+use crate::translation_data::TranslationItem;
+use std::fmt::Write;
+
+// Builds a shareable Markdown summary: overall progress, a per-namespace table
+// and the lists of keys still waiting for a translation or translated too long.
+pub fn markdown_report(tree: &[TreeNode], items: &[TranslationItem], length_ratio: f64) -> String {
+    let total = items.len();
+    let translated = items.iter().filter(|item| item.is_translated()).count();
+    let percentage = if total > 0 {
+        (translated as f64 / total as f64) * 100.0
+    } else {
+        0.0
+    };
+
+    let mut report = String::new();
+    let _ = writeln!(report, "# Translation Progress\n");
+    let _ = writeln!(
+        report,
+        "**Translated:** {}/{} ({:.2}%)\n",
+        translated, total, percentage
+    );
+
+    let _ = writeln!(report, "## Namespaces\n");
+    let _ = writeln!(report, "| Namespace | Translated | Total | Progress |");
+    let _ = writeln!(report, "|---|---:|---:|---:|");
+    for node in tree {
+        let (done, all) = node.leaf_counts();
+        let progress = if all > 0 {
+            (done as f64 / all as f64) * 100.0
+        } else {
+            0.0
+        };
+        let _ = writeln!(
+            report,
+            "| `{}` | {} | {} | {:.2}% |",
+            node.key_segment, done, all, progress
+        );
+    }
+
+    let mut untranslated: Vec<&str> = items
+        .iter()
+        .filter(|item| !item.is_translated())
+        .map(|item| item.key.as_str())
+        .collect();
+    untranslated.sort();
+
+    let _ = writeln!(report, "\n## Untranslated Keys ({})\n", untranslated.len());
+    for key in untranslated {
+        let _ = writeln!(report, "- `{}`", key);
+    }
+
+    let mut missing: Vec<(&str, Vec<String>)> = items
+        .iter()
+        .map(|item| (item.key.as_str(), item.missing_placeholders()))
+        .filter(|(_, placeholders)| !placeholders.is_empty())
+        .collect();
+    missing.sort();
+    if !missing.is_empty() {
+        let _ = writeln!(report, "\n## Missing Placeholders ({})\n", missing.len());
+        for (key, placeholders) in missing {
+            let _ = writeln!(report, "- `{}`: `{}`", key, placeholders.join("`, `"));
+        }
+    }
+
+    let mut whitespace: Vec<&str> = items
+        .iter()
+        .filter(|item| item.has_edge_whitespace_mismatch())
+        .map(|item| item.key.as_str())
+        .collect();
+    whitespace.sort();
+    if !whitespace.is_empty() {
+        let _ = writeln!(
+            report,
+            "\n## Leading/Trailing Whitespace Differs From the Source ({})\n",
+            whitespace.len()
+        );
+        for key in whitespace {
+            let _ = writeln!(report, "- `{}`", key);
+        }
+    }
+
+    let mut too_long: Vec<&str> = items
+        .iter()
+        .filter(|item| item.exceeds_length_ratio(length_ratio))
+        .map(|item| item.key.as_str())
+        .collect();
+    too_long.sort();
+    if !too_long.is_empty() {
+        let _ = writeln!(
+            report,
+            "\n## Longer Than {}x the Source ({})\n",
+            length_ratio,
+            too_long.len()
+        );
+        for key in too_long {
+            let _ = writeln!(report, "- `{}`", key);
+        }
+    }
+    report
+}
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+// State that survives restarts, kept per output file in
+// `$XDG_STATE_HOME/twoson/session.json` (default `~/.local/state`).
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct SessionState {
+    pub bookmarks: Vec<String>,
+    // Earlier translations of each key, oldest first
+    pub history: HashMap<String, Vec<String>>,
+    // Wrap long texts in the source panel instead of cutting them off
+    pub wrap_source: bool,
+}
+
+// Previous translations remembered per key before the oldest is dropped
+const HISTORY_LIMIT: usize = 10;
+
+impl SessionState {
+    // Remembers `text` as a previous translation of `key`; returns false when
+    // it was already the most recent entry.
+    pub fn push_history(&mut self, key: &str, text: String) -> bool {
+        let entries = self.history.entry(key.to_string()).or_default();
+        if entries.last() == Some(&text) {
+            return false;
+        }
+        entries.push(text);
+        if entries.len() > HISTORY_LIMIT {
+            entries.remove(0);
+        }
+        true
+    }
+
+    // A missing or unreadable state file just means a fresh session.
+    pub fn load(output_path: &Path) -> Self {
+        let Some(path) = Self::state_path() else {
+            return SessionState::default();
+        };
+        Self::read_all(&path)
+            .remove(&Self::session_key(output_path))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, output_path: &Path) -> Result<(), Box<dyn Error>> {
+        let path = Self::state_path().ok_or("No state directory (HOME is not set).")?;
+        let mut sessions = Self::read_all(&path);
+        sessions.insert(Self::session_key(output_path), self.clone());
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let contents = serde_json::to_string_pretty(&sessions)?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    fn read_all(path: &Path) -> HashMap<String, SessionState> {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    // The output file may not exist yet, so it can't be canonicalized.
+    fn session_key(output_path: &Path) -> String {
+        std::path::absolute(output_path)
+            .unwrap_or_else(|_| output_path.to_path_buf())
+            .display()
+            .to_string()
+    }
+
+    fn state_path() -> Option<PathBuf> {
+        let state_home = std::env::var_os("XDG_STATE_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/state"))
+            })?;
+        Some(state_home.join("twoson").join("session.json"))
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::translation_data::{FillMissing, TranslationItem, TranslationStore, is_stdio};
+
+// Hash of each key's source text as it was when the translation was last
+// saved, kept beside the output in `<out>.sources.json`. A translation whose
+// source hashes differently now was made for an older source and may be stale.
+pub type SourceHashes = BTreeMap<String, String>;
+
+fn hashes_path(output_path: &Path) -> Option<PathBuf> {
+    if is_stdio(output_path) {
+        return None;
+    }
+    let mut file_name = output_path.file_name()?.to_os_string();
+    file_name.push(".sources.json");
+    Some(output_path.with_file_name(file_name))
+}
+
+// 64-bit FNV-1a, which unlike std's hasher is stable across Rust versions.
+pub fn hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+// No hashes file yet means nothing is known to be stale.
+pub fn load(output_path: &Path) -> Result<SourceHashes, Box<dyn Error>> {
+    let Some(path) = hashes_path(output_path).filter(|path| path.exists()) else {
+        return Ok(SourceHashes::new());
+    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("{}: {}", path.display(), e).into())
+}
+
+// Records the current source of every translation edited since the last save,
+// and of any translation with no hash yet; the others keep the hash they were
+// made against. Untranslated and removed keys are dropped.
+pub fn update(hashes: &mut SourceHashes, store: &TranslationStore) {
+    hashes.retain(|key, _| {
+        store
+            .all_items
+            .get(key)
+            .is_some_and(|item| item.is_translated())
+    });
+    for (key, item) in &store.all_items {
+        if item.is_translated() && (store.is_modified(key) || !hashes.contains_key(key)) {
+            hashes.insert(key.clone(), hash(&item.source_text));
+        }
+    }
+}
+
+// A key saved under `--fill-missing` holds the source text or "" without having
+// been translated, and so without a hash. Such keys are loaded as untranslated
+// again; a translation that happens to read the same has a hash and is kept.
+pub fn unfill(items: &mut [TranslationItem], fill_missing: FillMissing, hashes: &SourceHashes) {
+    for item in items {
+        let filled = match fill_missing {
+            FillMissing::Source => item.source_text.as_str(),
+            FillMissing::Empty => "",
+            FillMissing::Skip => return,
+        };
+        if item.target_text.as_deref() == Some(filled) && !hashes.contains_key(&item.key) {
+            item.target_text = None;
+        }
+    }
+}
+
+pub fn save(output_path: &Path, hashes: &SourceHashes) -> Result<(), Box<dyn Error>> {
+    let Some(path) = hashes_path(output_path) else {
+        return Ok(());
+    };
+    if hashes.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+        return Ok(());
+    }
+    let contents = serde_json::to_string_pretty(hashes)?;
+    std::fs::write(&path, contents + "\n")
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
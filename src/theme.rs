@@ -0,0 +1,102 @@
+use ratatui::style::Color;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    Dark,
+    Light,
+    Mono,
+    Solarized,
+}
+
+// The palette used by every render function.
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub translated: Color,
+    pub untranslated: Color,
+    pub needs_review: Color,
+    pub identical: Color,
+    pub folder_expanded: Color,
+    pub folder_collapsed: Color,
+    pub selection: Color,
+    pub accent: Color,
+    pub muted: Color,
+    pub header_fg: Color,
+    pub header_bg: Color,
+    pub removed: Color,
+    pub added: Color,
+}
+
+impl Theme {
+    // `mono` keeps the dark palette around, but callers disable colors for it.
+    pub fn from_name(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark | ThemeName::Mono => Theme::dark(),
+            ThemeName::Light => Theme::light(),
+            ThemeName::Solarized => Theme::solarized(),
+        }
+    }
+
+    fn dark() -> Self {
+        Theme {
+            translated: Color::Green,
+            untranslated: Color::LightRed,
+            needs_review: Color::Yellow,
+            identical: Color::Magenta,
+            folder_expanded: Color::Blue,
+            folder_collapsed: Color::LightCyan,
+            selection: Color::Cyan,
+            accent: Color::LightYellow,
+            muted: Color::DarkGray,
+            header_fg: Color::White,
+            header_bg: Color::DarkGray,
+            removed: Color::LightRed,
+            added: Color::Green,
+        }
+    }
+
+    fn light() -> Self {
+        Theme {
+            translated: Color::Green,
+            untranslated: Color::Red,
+            needs_review: Color::Rgb(0xb5, 0x89, 0x00),
+            identical: Color::Magenta,
+            folder_expanded: Color::Blue,
+            folder_collapsed: Color::Cyan,
+            selection: Color::Blue,
+            accent: Color::Rgb(0xaf, 0x5f, 0x00),
+            muted: Color::Gray,
+            header_fg: Color::Black,
+            header_bg: Color::Gray,
+            removed: Color::Red,
+            added: Color::Green,
+        }
+    }
+
+    fn solarized() -> Self {
+        // https://ethanschoonover.com/solarized/
+        let yellow = Color::Rgb(0xb5, 0x89, 0x00);
+        let orange = Color::Rgb(0xcb, 0x4b, 0x16);
+        let red = Color::Rgb(0xdc, 0x32, 0x2f);
+        let magenta = Color::Rgb(0xd3, 0x36, 0x82);
+        let blue = Color::Rgb(0x26, 0x8b, 0xd2);
+        let cyan = Color::Rgb(0x2a, 0xa1, 0x98);
+        let green = Color::Rgb(0x85, 0x99, 0x00);
+        Theme {
+            translated: green,
+            untranslated: red,
+            needs_review: yellow,
+            identical: magenta,
+            folder_expanded: blue,
+            folder_collapsed: cyan,
+            selection: cyan,
+            accent: orange,
+            muted: Color::Rgb(0x58, 0x6e, 0x75),
+            header_fg: Color::Rgb(0xfd, 0xf6, 0xe3),
+            header_bg: Color::Rgb(0x07, 0x36, 0x42),
+            removed: red,
+            added: green,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, channel};
+
+pub struct SourceWatcher {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<Event>>,
+    watched_dir: PathBuf,
+    file_name: PathBuf,
+}
+
+impl SourceWatcher {
+    pub fn new(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let (sender, receiver) = channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+
+        // Watch the parent directory instead of the file itself, because many
+        // editors save by writing a new file and renaming it over the old one.
+        let file_name = path
+            .file_name()
+            .map(PathBuf::from)
+            .ok_or("Source file path has no filename.")?;
+        let watched_dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        watcher
+            .watch(&watched_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", watched_dir.display(), e))?;
+
+        Ok(SourceWatcher {
+            watcher,
+            receiver,
+            watched_dir,
+            file_name,
+        })
+    }
+
+    // Drains pending events and reports whether any of them touched the source file.
+    pub fn has_changed(&self) -> bool {
+        let mut changed = false;
+        while let Ok(result) = self.receiver.try_recv() {
+            if let Ok(event) = result
+                && (event.kind.is_modify() || event.kind.is_create())
+                && event
+                    .paths
+                    .iter()
+                    .any(|p| p.file_name() == Some(self.file_name.as_os_str()))
+            {
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn stop(mut self) {
+        let _ = self.watcher.unwatch(&self.watched_dir);
+    }
+}